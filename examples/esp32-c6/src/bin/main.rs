@@ -29,6 +29,7 @@ use esp_hal::timer::timg::TimerGroup;
 #[allow(unused_imports)]
 use log::{debug, error, info, warn};
 
+use i2c_devices::ht16k33::LedDisplay;
 use i2c_devices::ht16k33::SegmentedDisplay;
 
 extern crate alloc;
@@ -115,25 +116,25 @@ pub async fn i2c_task(mut i2c_bus: esp_hal::i2c::master::I2c<'static, esp_hal::B
     i2c_devices::emc2101::reset_device_registers(&mut ibd);
 
     // mutable allows us to change blink rate and brightness later on
-    let mut sd0 = i2c_devices::ht16k33::Segment14x4 {
-        convert: i2c_devices::ht16k33::convert_14,
-        did: 0,
-        display_mode: i2c_devices::ht16k33::DisplayMode::BlinkFast,
-        brightness_level: 8,
-    };
+    let mut sd0 = i2c_devices::ht16k33::Segment14x4::new(
+        i2c_devices::ht16k33::convert_14,
+        0,
+        i2c_devices::ht16k33::BlinkRate::Blink2Hz,
+        8,
+    ).unwrap();
 
     // configure display #0
-    sd0.set_display_mode(&mut ibd, i2c_devices::ht16k33::DisplayMode::BlinkSlow);
+    sd0.set_display_mode(&mut ibd, i2c_devices::ht16k33::BlinkRate::BlinkHalfHz);
     sd0.set_brightness_level(&mut ibd, 1);
     sd0.show_string(&mut ibd, "1234");
 
     // non-mutable is sufficient if we don't want to change display settings
-    let sd1 = i2c_devices::ht16k33::Segment14x4 {
-        convert: i2c_devices::ht16k33::convert_14,
-        did: 1,
-        display_mode: i2c_devices::ht16k33::DisplayMode::On,
-        brightness_level: 1,
-    };
+    let sd1 = i2c_devices::ht16k33::Segment14x4::new(
+        i2c_devices::ht16k33::convert_14,
+        1,
+        i2c_devices::ht16k33::BlinkRate::NoBlink,
+        1,
+    ).unwrap();
 
     // configure display #1
     sd1.show_buffer(
@@ -167,9 +168,9 @@ pub async fn i2c_task(mut i2c_bus: esp_hal::i2c::master::I2c<'static, esp_hal::B
 
     // temporarily disable display #0
     Timer::after(Duration::from_secs(5)).await;
-    sd0.set_display_mode(&mut ibd, i2c_devices::ht16k33::DisplayMode::Off);
+    sd0.set_display_mode(&mut ibd, i2c_devices::ht16k33::BlinkRate::DisplayOff);
     Timer::after(Duration::from_secs(2)).await;
-    sd0.set_display_mode(&mut ibd, i2c_devices::ht16k33::DisplayMode::On);
+    sd0.set_display_mode(&mut ibd, i2c_devices::ht16k33::BlinkRate::NoBlink);
 
     // show some numbers on both displays
     sd0.show_number(&mut ibd, 1.234);
@@ -213,6 +214,19 @@ impl<'a, Dm: esp_hal::DriverMode> i2c_devices::I2cBusDevice for I2cBusDevice<'a,
         rb[0]
     }
 
+    fn read_register_as_word(&mut self, da: u8, dr: u8) -> [u8; 2] {
+        let mut rb = [0u8; 2];
+
+        // write the register pointer, then read both bytes in a single
+        // transaction (repeated start condition)
+        if let Err(reason) = self.i2c_bus.write_read(da, &[dr], &mut rb) {
+            warn!("Failed to read register '{0:#04X}': {reason:?}", dr);
+        }
+
+        // implicit return
+        rb
+    }
+
     fn write_register_as_byte(&mut self, da: u8, dr: u8, byte: u8) {
         // TODO add error handling for write_register_as_u8()
         let _ = self.i2c_bus.write(da, &[dr, byte]);
@@ -256,6 +270,22 @@ impl<'a, Dm: esp_hal::DriverMode> i2c_devices::I2cBusDevice for I2cBusDevice<'a,
         }
     }
 
+    fn read_register_block(&mut self, da: u8, dr: u8, buffer: &mut [u8]) {
+        // the device's address pointer auto-increments after each byte
+        if let Err(reason) = self.i2c_bus.write_read(da, &[dr], buffer) {
+            warn!("Failed to read registers starting at '{0:#04X}': {reason}", dr);
+        }
+    }
+
+    fn write_register_block(&mut self, da: u8, dr: u8, bytes: &[u8]) {
+        // adjacent write operations are merged into a single write
+        // (no repeated start condition between register and data)
+        let mut operations = [esp_hal::i2c::master::Operation::Write(&[dr]), esp_hal::i2c::master::Operation::Write(bytes)];
+        if let Err(reason) = self.i2c_bus.transaction(da, &mut operations) {
+            warn!("Failed to write registers starting at '{0:#04X}': {reason}", dr);
+        }
+    }
+
     // some functions require a little time to pass
     // the sleep function is hardware-dependent and must be provided by
     // the caller
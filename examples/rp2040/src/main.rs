@@ -22,6 +22,7 @@ use log::{debug, error, info, warn};
 // (use either 'rp2040_hal' or 'rp2350_hal')
 use rp2040_hal as hal;
 
+use i2c_devices::ht16k33::LedDisplay;
 use i2c_devices::ht16k33::SegmentedDisplay;
 
 // this trait is required for '400.kHz()'
@@ -106,26 +107,26 @@ fn main() -> ! {
     i2c_devices::emc2101::reset_device_registers(&mut ibd);
 
     // mutable allows us to change blink rate and brightness later on
-    let mut sd1 = i2c_devices::ht16k33::Segment7x4 {
-        convert: i2c_devices::ht16k33::convert_7,
-        did: 1,
-        display_mode: i2c_devices::ht16k33::DisplayMode::On,
-        brightness_level: 1,
-    };
+    let mut sd1 = i2c_devices::ht16k33::Segment7x4::new(
+        i2c_devices::ht16k33::convert_7,
+        1,
+        i2c_devices::ht16k33::BlinkRate::NoBlink,
+        1,
+    ).unwrap();
 
     // change blink rate and brightness
-    sd1.set_display_mode(&mut ibd, i2c_devices::ht16k33::DisplayMode::On);
+    sd1.set_display_mode(&mut ibd, i2c_devices::ht16k33::BlinkRate::NoBlink);
     sd1.set_brightness_level(&mut ibd, 1);
     // write data
     sd1.show_string(&mut ibd, "12:34");
 
     // non-mutable is sufficient if we don't want to change display settings
-    let sd2 = i2c_devices::ht16k33::Segment7x4 {
-        convert: i2c_devices::ht16k33::convert_7,
-        did: 2,
-        display_mode: i2c_devices::ht16k33::DisplayMode::BlinkSlow,
-        brightness_level: 8,
-    };
+    let sd2 = i2c_devices::ht16k33::Segment7x4::new(
+        i2c_devices::ht16k33::convert_7,
+        2,
+        i2c_devices::ht16k33::BlinkRate::BlinkHalfHz,
+        8,
+    ).unwrap();
 
     // write data
     sd2.show_number(&mut ibd, 3.456);
@@ -185,6 +186,19 @@ where
         rb[0]
     }
 
+    fn read_register_as_word(&mut self, da: u8, dr: u8) -> [u8; 2] {
+        let mut rb = [0u8; 2];
+
+        // write the register pointer, then read both bytes in a single
+        // transaction (repeated start condition)
+        if let Err(reason) = self.i2c_bus.write_read(da, &[dr], &mut rb) {
+            warn!("Failed to read register '{0:#04X}': {reason:?}", dr);
+        }
+
+        // implicit return
+        rb
+    }
+
     fn write_register_as_byte(&mut self, da: u8, dr: u8, byte: u8) {
         // TODO add error handling for write_register_as_u8()
         let _ = self.i2c_bus.write(da, &[dr, byte]);
@@ -225,6 +239,22 @@ where
         }
     }
 
+    fn read_register_block(&mut self, da: u8, dr: u8, buffer: &mut [u8]) {
+        // the device's address pointer auto-increments after each byte
+        if let Err(reason) = self.i2c_bus.write_read(da, &[dr], buffer) {
+            warn!("Failed to read registers starting at '{0:#04X}': {reason:?}", dr);
+        }
+    }
+
+    fn write_register_block(&mut self, da: u8, dr: u8, bytes: &[u8]) {
+        // adjacent write operations are merged into a single write
+        // (no repeated start condition between register and data)
+        let mut operations = [embedded_hal::i2c::Operation::Write(&[dr]), embedded_hal::i2c::Operation::Write(bytes)];
+        if let Err(reason) = self.i2c_bus.transaction(da, &mut operations) {
+            warn!("Failed to write registers starting at '{0:#04X}': {reason:?}", dr);
+        }
+    }
+
     // some hardware functions require a little time to pass
     // - functions that sleep mention this fact in their documentation
     // - sleeping is hardware-dependent, no_std provides no abstraction
@@ -0,0 +1,63 @@
+/*
+    the I²C address is selected by connecting the ADDR pin
+
+    | ADDR pin | address |
+    |:---------|:-------:|
+    | GND      | 0x48    |
+    | VDD      | 0x49    |
+    | SDA      | 0x4A    |
+    | SCL      | 0x4B    |
+*/
+
+use crate::ads1x1x::Error;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the pin the ADDR pin is connected to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AddressPin {
+    Gnd = 0x48,
+    Vdd = 0x49,
+    Sda = 0x4A,
+    Scl = 0x4B,
+}
+
+impl AddressPin {
+    /// the device's I²C address
+    pub const fn get_address(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for AddressPin {
+    type Error = Error;
+
+    /// convert an I²C address (0x48 ≤ x ≤ 0x4B)
+    fn try_from(address: u8) -> Result<Self, Self::Error> {
+        match address {
+            0x48 => Ok(AddressPin::Gnd),
+            0x49 => Ok(AddressPin::Vdd),
+            0x4A => Ok(AddressPin::Sda),
+            0x4B => Ok(AddressPin::Scl),
+            _ => Err(Error::InvalidAddress(address)),
+        }
+    }
+}
+
+#[test]
+fn convert_address_pass() {
+    let computed = [
+        AddressPin::try_from(0x48),
+        AddressPin::try_from(0x4B),
+        AddressPin::try_from(0x4C),
+    ];
+    let expected = [
+        Ok(AddressPin::Gnd),
+        Ok(AddressPin::Scl),
+        Err(Error::InvalidAddress(0x4C)),
+    ];
+
+    assert_eq!(computed, expected);
+    assert_eq!(AddressPin::Sda.get_address(), 0x4A);
+}
@@ -0,0 +1,71 @@
+/*
+    the input multiplexer (ADS1x15 only)
+
+    The ADS1x13 and ADS1x14 have no multiplexer, they always measure the
+    differential input AIN0 - AIN1. Their driver takes a 'FixedInput'
+    instead of a 'Channel', selecting another input does not compile.
+*/
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the input (MUX bits of the config register)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Channel {
+    Diff01 = 0b000, // AIN0 - AIN1 (default)
+    Diff03 = 0b001, // AIN0 - AIN3
+    Diff13 = 0b010, // AIN1 - AIN3
+    Diff23 = 0b011, // AIN2 - AIN3
+    Ain0 = 0b100,   // AIN0 - GND
+    Ain1 = 0b101,   // AIN1 - GND
+    Ain2 = 0b110,   // AIN2 - GND
+    Ain3 = 0b111,   // AIN3 - GND
+}
+
+impl Channel {
+    /// returns 'true' if the input is measured against another input
+    pub const fn is_differential(&self) -> bool {
+        (*self as u8) < 0b100
+    }
+}
+
+impl From<u8> for Channel {
+    /// convert the MUX bits (only the lowest 3 bits are used)
+    fn from(value: u8) -> Self {
+        match value & 0b111 {
+            0b000 => Channel::Diff01,
+            0b001 => Channel::Diff03,
+            0b010 => Channel::Diff13,
+            0b011 => Channel::Diff23,
+            0b100 => Channel::Ain0,
+            0b101 => Channel::Ain1,
+            0b110 => Channel::Ain2,
+            _ => Channel::Ain3,
+        }
+    }
+}
+
+/// the input of the variants without multiplexer (ADS1x13, ADS1x14)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FixedInput {
+    Diff01, // AIN0 - AIN1
+}
+
+impl From<FixedInput> for Channel {
+    fn from(value: FixedInput) -> Self {
+        match value {
+            FixedInput::Diff01 => Channel::Diff01,
+        }
+    }
+}
+
+#[test]
+fn convert_channel() {
+    for value in 0..8 {
+        assert_eq!(Channel::from(value) as u8, value);
+    }
+
+    assert!(Channel::Diff23.is_differential());
+    assert!(!Channel::Ain0.is_differential());
+    assert_eq!(Channel::from(FixedInput::Diff01), Channel::Diff01);
+}
@@ -0,0 +1,133 @@
+/*
+    the data rate (DR bits of the config register)
+
+    The same bits select different data rates on the ADS101x and the
+    ADS111x:
+
+    | DR    | ADS101x  | ADS111x |
+    |:-----:|:--------:|:-------:|
+    | 0b000 | 128      | 8       |
+    | 0b001 | 250      | 16      |
+    | 0b010 | 490      | 32      |
+    | 0b011 | 920      | 64      |
+    | 0b100 | 1600 (*) | 128 (*) |
+    | 0b101 | 2400     | 250     |
+    | 0b110 | 3300     | 475     |
+    | 0b111 | 3300     | 860     |
+
+    (*) default
+*/
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// samples per second
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataRate {
+    Sps8,    // ADS111x only
+    Sps16,   // ADS111x only
+    Sps32,   // ADS111x only
+    Sps64,   // ADS111x only
+    Sps128,  // ADS101x & ADS111x
+    Sps250,  // ADS101x & ADS111x
+    Sps475,  // ADS111x only
+    Sps490,  // ADS101x only
+    Sps860,  // ADS111x only
+    Sps920,  // ADS101x only
+    Sps1600, // ADS101x only
+    Sps2400, // ADS101x only
+    Sps3300, // ADS101x only
+}
+
+impl DataRate {
+    pub const fn get_samples_per_second(&self) -> u32 {
+        match self {
+            DataRate::Sps8 => 8,
+            DataRate::Sps16 => 16,
+            DataRate::Sps32 => 32,
+            DataRate::Sps64 => 64,
+            DataRate::Sps128 => 128,
+            DataRate::Sps250 => 250,
+            DataRate::Sps475 => 475,
+            DataRate::Sps490 => 490,
+            DataRate::Sps860 => 860,
+            DataRate::Sps920 => 920,
+            DataRate::Sps1600 => 1600,
+            DataRate::Sps2400 => 2400,
+            DataRate::Sps3300 => 3300,
+        }
+    }
+
+    /// the maximum time a single conversion takes (in µs)
+    /// - the internal oscillator may be up to 10% slow
+    /// - waking up from power-down takes up to 25µs (rounded up to 50µs)
+    pub const fn get_conversion_time_us(&self) -> u32 {
+        let nominal = 1_000_000 / self.get_samples_per_second();
+
+        // implicit return
+        nominal + nominal / 10 + 50
+    }
+
+    /// the DR bits for the ADS101x ('None' if not supported)
+    pub(crate) const fn encode_12bit(&self) -> Option<u16> {
+        match self {
+            DataRate::Sps128 => Some(0b000),
+            DataRate::Sps250 => Some(0b001),
+            DataRate::Sps490 => Some(0b010),
+            DataRate::Sps920 => Some(0b011),
+            DataRate::Sps1600 => Some(0b100),
+            DataRate::Sps2400 => Some(0b101),
+            DataRate::Sps3300 => Some(0b110),
+            _ => None,
+        }
+    }
+
+    /// the DR bits for the ADS111x ('None' if not supported)
+    pub(crate) const fn encode_16bit(&self) -> Option<u16> {
+        match self {
+            DataRate::Sps8 => Some(0b000),
+            DataRate::Sps16 => Some(0b001),
+            DataRate::Sps32 => Some(0b010),
+            DataRate::Sps64 => Some(0b011),
+            DataRate::Sps128 => Some(0b100),
+            DataRate::Sps250 => Some(0b101),
+            DataRate::Sps475 => Some(0b110),
+            DataRate::Sps860 => Some(0b111),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn encode_data_rate() {
+    let computed = [
+        DataRate::Sps128.encode_12bit(),
+        DataRate::Sps128.encode_16bit(),
+        DataRate::Sps3300.encode_12bit(),
+        DataRate::Sps3300.encode_16bit(),
+        DataRate::Sps860.encode_12bit(),
+        DataRate::Sps860.encode_16bit(),
+    ];
+    let expected = [
+        Some(0b000),
+        Some(0b100),
+        Some(0b110),
+        None,
+        None,
+        Some(0b111),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn conversion_time() {
+    let computed = [
+        DataRate::Sps8.get_conversion_time_us(),
+        DataRate::Sps128.get_conversion_time_us(),
+        DataRate::Sps3300.get_conversion_time_us(),
+    ];
+    let expected = [137_550, 8_643, 383];
+
+    assert_eq!(computed, expected);
+}
@@ -0,0 +1,335 @@
+/*
+    single-shot conversions
+
+    A conversion is started by writing the config register with the OS
+    bit set. The device powers up, converts the selected input once and
+    powers down again. The driver sleeps for the conversion time of the
+    selected data rate, afterwards the OS bit (reads 0 while the
+    conversion is in progress) is polled until the result is available.
+
+    continuous conversions
+
+    With the MODE bit cleared, the device converts the selected input
+    continuously at the selected data rate. The comparator is configured
+    in conversion-ready mode (Hi_thresh MSB = 1, Lo_thresh MSB = 0), the
+    ALERT/RDY pin pulses after each conversion. Setting the MODE bit
+    again powers the device down after the current conversion.
+*/
+
+use core::marker::PhantomData;
+
+use crate::ads1x1x::{hw, AddressPin, Channel, DataRate, Gain, HasPga, Variant};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+// additional polls if the conversion took longer than expected
+const MAX_POLLS: u32 = 10;
+const POLL_INTERVAL_MS: u32 = 1;
+
+/// the requested operation is not supported (or failed)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    /// the I²C address must be in range 0x48 ≤ x ≤ 0x4B
+    InvalidAddress(u8),
+    /// the data rate is not supported by the variant
+    /// (ADS101x: 128..3300 SPS, ADS111x: 8..860 SPS)
+    UnsupportedDataRate(DataRate),
+    /// the conversion did not finish in time
+    Timeout,
+}
+
+/// an ADS1x1x analog-to-digital converter
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut adc = Ads1x1x::<Ads1115>::new(AddressPin::Gnd);
+/// adc.set_gain(Gain::Fsr4096);
+/// adc.set_data_rate(DataRate::Sps860)?;
+/// let raw = adc.read_raw(&mut ibd, Channel::Ain0)?;
+/// let mv = adc.read_millivolts(&mut ibd, Channel::Diff23)?;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ads1x1x<V> {
+    pub address: AddressPin,
+    gain: Gain,
+    data_rate: DataRate,
+    variant: PhantomData<V>,
+}
+
+impl<V> Ads1x1x<V>
+where
+    V: Variant,
+{
+    /// create a driver using the default gain (±2.048V) and data rate
+    pub const fn new(address: AddressPin) -> Self {
+        Ads1x1x {
+            address,
+            gain: Gain::Fsr2048,
+            data_rate: V::DEFAULT_DATA_RATE,
+            variant: PhantomData,
+        }
+    }
+
+    /// create a driver using the provided data rate
+    pub fn with_data_rate(address: AddressPin, data_rate: DataRate) -> Result<Self, Error> {
+        let mut adc = Self::new(address);
+        adc.set_data_rate(data_rate)?;
+
+        // implicit return
+        Ok(adc)
+    }
+
+    pub fn get_gain(&self) -> Gain {
+        self.gain
+    }
+
+    pub fn get_data_rate(&self) -> DataRate {
+        self.data_rate
+    }
+
+    /// change the data rate (applied with the next conversion)
+    pub fn set_data_rate(&mut self, data_rate: DataRate) -> Result<(), Error> {
+        if V::encode_data_rate(data_rate).is_none() {
+            error!("Data rate {data_rate:?} is not supported by this variant");
+            return Err(Error::UnsupportedDataRate(data_rate));
+        }
+        self.data_rate = data_rate;
+
+        // implicit return
+        Ok(())
+    }
+
+    /// convert the provided input once and return the raw counts
+    /// - the input is a 'Channel' (ADS1x15) or a 'FixedInput' (ADS1x13, ADS1x14)
+    /// - range: -2048 ≤ x ≤ 2047 (12 bit), -32768 ≤ x ≤ 32767 (16 bit)
+    /// - sleeps while the conversion is in progress
+    pub fn read_raw<Ibd>(&self, ibd: &mut Ibd, channel: V::Input) -> Result<i16, Error>
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.address.get_address();
+        hw::set_config(ibd, da, hw::CONFIG_OS | self.create_config(channel.into()));
+        self.wait_for_conversion(ibd)?;
+
+        // implicit return
+        Ok(self.convert_to_counts(hw::get_conversion(ibd, da)))
+    }
+
+    /// convert the provided input once and return the voltage
+    /// (same rules as read_raw())
+    pub fn read_millivolts<Ibd>(&self, ibd: &mut Ibd, channel: V::Input) -> Result<f32, Error>
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let raw = self.read_raw(ibd, channel)?;
+
+        // implicit return
+        Ok(self.convert_to_millivolts(raw))
+    }
+
+    /// start converting the provided input continuously
+    /// - ALERT/RDY pulses after each conversion (ADS1x14 and ADS1x15 only)
+    /// - read the results using read_latest()
+    pub fn start_continuous<Ibd>(&self, ibd: &mut Ibd, channel: V::Input)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.address.get_address();
+        if V::HAS_COMPARATOR {
+            hw::set_hi_thresh(ibd, da, hw::HI_THRESH_READY);
+            hw::set_lo_thresh(ibd, da, hw::LO_THRESH_READY);
+        }
+        hw::set_config(ibd, da, self.create_continuous_config(channel.into()));
+    }
+
+    /// return the result of the most recent conversion (in counts)
+    /// - does not start a new conversion
+    /// - if continuous mode was never started, this is the result of the
+    ///   last single-shot conversion (or 0 after power-up)
+    pub fn read_latest<Ibd>(&self, ibd: &mut Ibd) -> i16
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.address.get_address();
+
+        // implicit return
+        self.convert_to_counts(hw::get_conversion(ibd, da))
+    }
+
+    /// stop continuous conversions and return to single-shot mode
+    /// - the device powers down after the current conversion
+    /// - the comparator and the thresholds are reset to their defaults
+    pub fn power_down<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.address.get_address();
+        let config = hw::get_config(ibd, da);
+        hw::set_config(
+            ibd,
+            da,
+            (config & !hw::CONFIG_OS) | hw::CONFIG_MODE | hw::CONFIG_COMP_QUE_DISABLE,
+        );
+        if V::HAS_COMPARATOR {
+            hw::set_hi_thresh(ibd, da, hw::HI_THRESH_DEFAULT);
+            hw::set_lo_thresh(ibd, da, hw::LO_THRESH_DEFAULT);
+        }
+    }
+
+    /// convert the content of the conversion register to counts
+    /// (12 bit results are left-aligned)
+    pub fn convert_to_counts(&self, value: u16) -> i16 {
+        let shift = 16 - V::RESOLUTION;
+
+        // implicit return
+        (value as i16) >> shift
+    }
+
+    /// convert raw counts to millivolts (using the current gain)
+    pub fn convert_to_millivolts(&self, raw: i16) -> f32 {
+        let counts = (1u32 << (V::RESOLUTION - 1)) as f32;
+
+        // implicit return
+        raw as f32 * self.gain.get_full_scale_mv() / counts
+    }
+
+    /// the config register for a single conversion of the provided input
+    /// (without the OS bit, comparator disabled)
+    pub(crate) fn create_config(&self, channel: Channel) -> u16 {
+        // validated when the data rate was set
+        let data_rate = V::encode_data_rate(self.data_rate).unwrap_or(0b100);
+
+        // implicit return
+        (channel as u16) << hw::CONFIG_MUX_SHIFT
+            | (self.gain as u16) << hw::CONFIG_PGA_SHIFT
+            | hw::CONFIG_MODE
+            | data_rate << hw::CONFIG_DR_SHIFT
+            | hw::CONFIG_COMP_QUE_DISABLE
+    }
+
+    /// the config register for continuous conversions of the provided input
+    /// (ALERT/RDY asserted after each conversion if available)
+    pub(crate) fn create_continuous_config(&self, channel: Channel) -> u16 {
+        let config = self.create_config(channel) & !hw::CONFIG_MODE;
+        if !V::HAS_COMPARATOR {
+            return config;
+        }
+
+        // implicit return
+        (config & !hw::CONFIG_COMP_QUE_DISABLE) | hw::CONFIG_COMP_QUE_ONE
+    }
+
+    /// sleep for the conversion time, then poll the OS bit until the
+    /// conversion has finished
+    fn wait_for_conversion<Ibd>(&self, ibd: &mut Ibd) -> Result<(), Error>
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.address.get_address();
+        let conversion_time_ms = self.data_rate.get_conversion_time_us().div_ceil(1000);
+        ibd.sleep_ms(conversion_time_ms);
+
+        for _ in 0..MAX_POLLS {
+            if hw::get_config(ibd, da) & hw::CONFIG_OS != 0 {
+                return Ok(());
+            }
+            ibd.sleep_ms(POLL_INTERVAL_MS);
+        }
+
+        error!("Conversion on {da:#04X} did not finish in time");
+
+        // implicit return
+        Err(Error::Timeout)
+    }
+}
+
+impl<V> Ads1x1x<V>
+where
+    V: HasPga,
+{
+    /// change the full-scale range (applied with the next conversion)
+    /// (only available for variants with PGA)
+    pub fn set_gain(&mut self, gain: Gain) {
+        self.gain = gain;
+    }
+}
+
+#[cfg(test)]
+use crate::ads1x1x::{Ads1013, Ads1015, Ads1113, Ads1115};
+
+#[test]
+fn create_config_pass() {
+    let ads1115 = Ads1x1x::<Ads1115>::new(AddressPin::Gnd);
+    let mut ads1015 =
+        Ads1x1x::<Ads1015>::with_data_rate(AddressPin::Gnd, DataRate::Sps128).unwrap();
+    ads1015.set_gain(Gain::Fsr256);
+    let computed = [
+        ads1115.create_config(Channel::Diff01),
+        ads1115.create_config(Channel::Ain2),
+        ads1015.create_config(Channel::Ain0),
+    ];
+    // the default config (0x8583) without the OS bit
+    let expected = [0x0583, 0x6583, 0x4B03];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn create_continuous_config_pass() {
+    let ads1115 = Ads1x1x::<Ads1115>::with_data_rate(AddressPin::Gnd, DataRate::Sps860).unwrap();
+    let ads1113 = Ads1x1x::<Ads1113>::new(AddressPin::Gnd);
+    let computed = [
+        ads1115.create_continuous_config(Channel::Ain0),
+        ads1113.create_continuous_config(Channel::Diff01),
+    ];
+    let expected = [0x44E0, 0x0483];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn set_data_rate_fail() {
+    let mut ads1013 = Ads1x1x::<Ads1013>::new(AddressPin::Gnd);
+    let computed = [
+        ads1013.set_data_rate(DataRate::Sps8),
+        ads1013.set_data_rate(DataRate::Sps3300),
+    ];
+    let expected = [Err(Error::UnsupportedDataRate(DataRate::Sps8)), Ok(())];
+
+    assert_eq!(computed, expected);
+    assert_eq!(ads1013.get_data_rate(), DataRate::Sps3300);
+}
+
+#[test]
+fn convert_to_counts_pass() {
+    let ads1015 = Ads1x1x::<Ads1015>::new(AddressPin::Gnd);
+    let ads1115 = Ads1x1x::<Ads1115>::new(AddressPin::Gnd);
+    let computed = [
+        ads1015.convert_to_counts(0x7FF0),
+        ads1015.convert_to_counts(0x8000),
+        ads1015.convert_to_counts(0xFFF0),
+        ads1115.convert_to_counts(0x7FFF),
+        ads1115.convert_to_counts(0xFFFF),
+    ];
+    let expected = [2047, -2048, -1, 32767, -1];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn convert_to_millivolts_pass() {
+    let ads1015 = Ads1x1x::<Ads1015>::new(AddressPin::Gnd);
+    let mut ads1115 = Ads1x1x::<Ads1115>::new(AddressPin::Gnd);
+    let computed_default = [
+        ads1015.convert_to_millivolts(1000),
+        ads1015.convert_to_millivolts(-2048),
+        ads1115.convert_to_millivolts(16000),
+    ];
+    ads1115.set_gain(Gain::Fsr6144);
+    let computed_gain = ads1115.convert_to_millivolts(16000);
+
+    assert_eq!(computed_default, [1000.0, -2048.0, 1000.0]);
+    assert_eq!(computed_gain, 3000.0);
+}
@@ -0,0 +1,58 @@
+/*
+    the programmable gain amplifier (ADS1x14 and ADS1x15 only)
+
+    The gain defines the full-scale range (FSR). The analog inputs must
+    not exceed VDD + 0.3V, regardless of the selected range.
+*/
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the full-scale range (PGA bits of the config register)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gain {
+    Fsr6144 = 0b000, // ±6.144V
+    Fsr4096 = 0b001, // ±4.096V
+    Fsr2048 = 0b010, // ±2.048V (default)
+    Fsr1024 = 0b011, // ±1.024V
+    Fsr512 = 0b100,  // ±0.512V
+    Fsr256 = 0b101,  // ±0.256V
+}
+
+impl Gain {
+    /// the full-scale range in millivolts
+    pub const fn get_full_scale_mv(&self) -> f32 {
+        match self {
+            Gain::Fsr6144 => 6144.0,
+            Gain::Fsr4096 => 4096.0,
+            Gain::Fsr2048 => 2048.0,
+            Gain::Fsr1024 => 1024.0,
+            Gain::Fsr512 => 512.0,
+            Gain::Fsr256 => 256.0,
+        }
+    }
+}
+
+impl From<u8> for Gain {
+    /// convert the PGA bits (only the lowest 3 bits are used)
+    fn from(value: u8) -> Self {
+        match value & 0b111 {
+            0b000 => Gain::Fsr6144,
+            0b001 => Gain::Fsr4096,
+            0b010 => Gain::Fsr2048,
+            0b011 => Gain::Fsr1024,
+            0b100 => Gain::Fsr512,
+            // all remaining values map to ±0.256V
+            _ => Gain::Fsr256,
+        }
+    }
+}
+
+#[test]
+fn convert_gain() {
+    let computed = [Gain::from(0b000), Gain::from(0b010), Gain::from(0b111)];
+    let expected = [Gain::Fsr6144, Gain::Fsr2048, Gain::Fsr256];
+
+    assert_eq!(computed, expected);
+    assert_eq!(Gain::Fsr512.get_full_scale_mv(), 512.0);
+}
@@ -0,0 +1,162 @@
+/*
+    raw, low-level access (as implemented by hardware)
+
+    All registers are 16 bit wide and transferred MSB first. A write
+    consists of the address pointer followed by the MSB and the LSB, a
+    read sets the address pointer and reads two bytes.
+
+    | pointer | register    | R/W | default |
+    |:-------:|:------------|:---:|:-------:|
+    | 0x00    | conversion  | R   | 0x0000  |
+    | 0x01    | config      | R/W | 0x8583  |
+    | 0x02    | Lo_thresh   | R/W | 0x8000  |
+    | 0x03    | Hi_thresh   | R/W | 0x7FFF  |
+*/
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+// ------------------------------------------------------------------------
+// constants
+// ------------------------------------------------------------------------
+
+/// the address pointer register
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Register {
+    Conversion = 0x00,
+    Config = 0x01,
+    LoThresh = 0x02,
+    HiThresh = 0x03,
+}
+
+// config register (bit 15): write 1 to start a single conversion,
+// reads 0 while a conversion is in progress
+pub const CONFIG_OS: u16 = 0b1000_0000_0000_0000;
+// config register (bit 14..12): input multiplexer
+pub const CONFIG_MUX_SHIFT: u16 = 12;
+// config register (bit 11..9): programmable gain amplifier
+pub const CONFIG_PGA_SHIFT: u16 = 9;
+// config register (bit 8): 0 = continuous, 1 = single-shot (power-down)
+pub const CONFIG_MODE: u16 = 0b0000_0001_0000_0000;
+// config register (bit 7..5): data rate
+pub const CONFIG_DR_SHIFT: u16 = 5;
+// config register (bit 1..0): comparator queue (0b11 = disabled)
+pub const CONFIG_COMP_QUE_DISABLE: u16 = 0b0000_0000_0000_0011;
+// config register (bit 1..0): assert ALERT/RDY after one conversion
+pub const CONFIG_COMP_QUE_ONE: u16 = 0b0000_0000_0000_0000;
+
+// conversion-ready mode: Hi_thresh MSB = 1 and Lo_thresh MSB = 0,
+// ALERT/RDY pulses after each conversion (ADS1x14 and ADS1x15 only)
+pub const HI_THRESH_READY: u16 = 0x8000;
+pub const LO_THRESH_READY: u16 = 0x0000;
+
+/// the config register after power-up
+pub const CONFIG_DEFAULT: u16 = 0x8583;
+/// the Lo_thresh register after power-up
+pub const LO_THRESH_DEFAULT: u16 = 0x8000;
+/// the Hi_thresh register after power-up
+pub const HI_THRESH_DEFAULT: u16 = 0x7FFF;
+
+// ------------------------------------------------------------------------
+// generic register access
+// ------------------------------------------------------------------------
+
+/// read a 16 bit register
+pub fn read_register<Ibd>(ibd: &mut Ibd, da: u8, register: Register) -> u16
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let buffer = ibd.read_register_as_word(da, register as u8);
+
+    // implicit return
+    u16::from_be_bytes(buffer)
+}
+
+/// write a 16 bit register
+/// (the conversion register is read-only)
+pub fn write_register<Ibd>(ibd: &mut Ibd, da: u8, register: Register, value: u16) -> bool
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    if register == Register::Conversion {
+        error!("The conversion register is read-only");
+        return false;
+    }
+
+    let [msb, lsb] = value.to_be_bytes();
+    debug!(
+        "Writing {0:#06X} to {1:?} on {2:#04X}.",
+        value, register, da
+    );
+    ibd.write_bytes(da, &[register as u8, msb, lsb]);
+
+    // implicit return
+    true
+}
+
+// ------------------------------------------------------------------------
+// conversion register (0x00)
+// ------------------------------------------------------------------------
+
+/// read the result of the last conversion
+/// (12 bit variants: the result is left-aligned, bit 3..0 are 0)
+pub fn get_conversion<Ibd>(ibd: &mut Ibd, da: u8) -> u16
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    // implicit return
+    read_register(ibd, da, Register::Conversion)
+}
+
+// ------------------------------------------------------------------------
+// config register (0x01)
+// ------------------------------------------------------------------------
+
+pub fn get_config<Ibd>(ibd: &mut Ibd, da: u8) -> u16
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    // implicit return
+    read_register(ibd, da, Register::Config)
+}
+
+pub fn set_config<Ibd>(ibd: &mut Ibd, da: u8, value: u16)
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    write_register(ibd, da, Register::Config, value);
+}
+
+// ------------------------------------------------------------------------
+// Lo_thresh and Hi_thresh registers (0x02, 0x03)
+// ------------------------------------------------------------------------
+
+pub fn get_lo_thresh<Ibd>(ibd: &mut Ibd, da: u8) -> u16
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    // implicit return
+    read_register(ibd, da, Register::LoThresh)
+}
+
+pub fn set_lo_thresh<Ibd>(ibd: &mut Ibd, da: u8, value: u16)
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    write_register(ibd, da, Register::LoThresh, value);
+}
+
+pub fn get_hi_thresh<Ibd>(ibd: &mut Ibd, da: u8) -> u16
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    // implicit return
+    read_register(ibd, da, Register::HiThresh)
+}
+
+pub fn set_hi_thresh<Ibd>(ibd: &mut Ibd, da: u8, value: u16)
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    write_register(ibd, da, Register::HiThresh, value);
+}
@@ -1,3 +1,20 @@
-//
-// stub
-//
+/*
+    interface for TI's ADS1x1x analog-to-digital converters
+    (ADS1013, ADS1014, ADS1015, ADS1113, ADS1114, ADS1115)
+*/
+
+pub mod hw;
+
+mod address;
+mod channel;
+mod data_rate;
+mod device;
+mod gain;
+mod variant;
+
+pub use address::AddressPin;
+pub use channel::{Channel, FixedInput};
+pub use data_rate::DataRate;
+pub use device::{Ads1x1x, Error};
+pub use gain::Gain;
+pub use variant::{Ads1013, Ads1014, Ads1015, Ads1113, Ads1114, Ads1115, HasPga, Variant};
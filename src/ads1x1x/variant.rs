@@ -0,0 +1,156 @@
+/*
+    members of the ADS1x1x family
+
+    | variant | resolution | PGA | MUX | comparator | data rate     |
+    |:--------|:----------:|:---:|:---:|:----------:|:--------------|
+    | ADS1013 | 12 bit     | -   | -   | -          | 128..3300 SPS |
+    | ADS1014 | 12 bit     | yes | -   | yes        | 128..3300 SPS |
+    | ADS1015 | 12 bit     | yes | yes | yes        | 128..3300 SPS |
+    | ADS1113 | 16 bit     | -   | -   | -          | 8..860 SPS    |
+    | ADS1114 | 16 bit     | yes | -   | yes        | 8..860 SPS    |
+    | ADS1115 | 16 bit     | yes | yes | yes        | 8..860 SPS    |
+
+    The variant is a type parameter of the driver. Setting the gain of a
+    variant without PGA or selecting a channel of a variant without MUX
+    does not compile, an unsupported data rate is rejected when it is set.
+*/
+
+use crate::ads1x1x::{Channel, DataRate, FixedInput};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the features of an ADS1x1x variant
+pub trait Variant {
+    /// the number of bits of a conversion result
+    const RESOLUTION: u8;
+    /// the variant has a comparator (and an ALERT/RDY pin)
+    const HAS_COMPARATOR: bool;
+    /// the data rate after power-up
+    const DEFAULT_DATA_RATE: DataRate;
+
+    /// the inputs the variant can measure
+    /// ('Channel' with MUX, 'FixedInput' without)
+    type Input: Copy + Into<Channel>;
+
+    /// the DR bits of the config register ('None' if not supported)
+    fn encode_data_rate(data_rate: DataRate) -> Option<u16>;
+}
+
+/// variants with a programmable gain amplifier
+pub trait HasPga: Variant {}
+
+/// ADS1013 (12 bit, no PGA, no MUX)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ads1013;
+
+impl Variant for Ads1013 {
+    const RESOLUTION: u8 = 12;
+    const HAS_COMPARATOR: bool = false;
+    const DEFAULT_DATA_RATE: DataRate = DataRate::Sps1600;
+
+    type Input = FixedInput;
+
+    fn encode_data_rate(data_rate: DataRate) -> Option<u16> {
+        data_rate.encode_12bit()
+    }
+}
+
+/// ADS1014 (12 bit, PGA, no MUX)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ads1014;
+
+impl Variant for Ads1014 {
+    const RESOLUTION: u8 = 12;
+    const HAS_COMPARATOR: bool = true;
+    const DEFAULT_DATA_RATE: DataRate = DataRate::Sps1600;
+
+    type Input = FixedInput;
+
+    fn encode_data_rate(data_rate: DataRate) -> Option<u16> {
+        data_rate.encode_12bit()
+    }
+}
+
+/// ADS1015 (12 bit, PGA, MUX)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ads1015;
+
+impl Variant for Ads1015 {
+    const RESOLUTION: u8 = 12;
+    const HAS_COMPARATOR: bool = true;
+    const DEFAULT_DATA_RATE: DataRate = DataRate::Sps1600;
+
+    type Input = Channel;
+
+    fn encode_data_rate(data_rate: DataRate) -> Option<u16> {
+        data_rate.encode_12bit()
+    }
+}
+
+/// ADS1113 (16 bit, no PGA, no MUX)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ads1113;
+
+impl Variant for Ads1113 {
+    const RESOLUTION: u8 = 16;
+    const HAS_COMPARATOR: bool = false;
+    const DEFAULT_DATA_RATE: DataRate = DataRate::Sps128;
+
+    type Input = FixedInput;
+
+    fn encode_data_rate(data_rate: DataRate) -> Option<u16> {
+        data_rate.encode_16bit()
+    }
+}
+
+/// ADS1114 (16 bit, PGA, no MUX)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ads1114;
+
+impl Variant for Ads1114 {
+    const RESOLUTION: u8 = 16;
+    const HAS_COMPARATOR: bool = true;
+    const DEFAULT_DATA_RATE: DataRate = DataRate::Sps128;
+
+    type Input = FixedInput;
+
+    fn encode_data_rate(data_rate: DataRate) -> Option<u16> {
+        data_rate.encode_16bit()
+    }
+}
+
+/// ADS1115 (16 bit, PGA, MUX)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ads1115;
+
+impl Variant for Ads1115 {
+    const RESOLUTION: u8 = 16;
+    const HAS_COMPARATOR: bool = true;
+    const DEFAULT_DATA_RATE: DataRate = DataRate::Sps128;
+
+    type Input = Channel;
+
+    fn encode_data_rate(data_rate: DataRate) -> Option<u16> {
+        data_rate.encode_16bit()
+    }
+}
+
+impl HasPga for Ads1014 {}
+impl HasPga for Ads1015 {}
+impl HasPga for Ads1114 {}
+impl HasPga for Ads1115 {}
+
+#[test]
+fn variant_features() {
+    let computed = [Ads1013::RESOLUTION, Ads1115::RESOLUTION];
+    let expected = [12, 16];
+
+    assert_eq!(computed, expected);
+    assert_eq!(
+        [Ads1113::HAS_COMPARATOR, Ads1114::HAS_COMPARATOR],
+        [false, true]
+    );
+    assert_eq!(Ads1013::encode_data_rate(DataRate::Sps8), None);
+    assert_eq!(Ads1113::encode_data_rate(DataRate::Sps8), Some(0b000));
+}
@@ -20,6 +20,9 @@ where
 
 /// compare currently stored values to default values
 /// (use after reset_device_registers())
+///
+/// An EMC2101-R which loaded its configuration from the EEPROM will
+/// report differences unless reset_device_registers() was called first.
 pub fn validate_device_registers<Ibd>(ibd: &mut Ibd) -> bool
 where
     Ibd: crate::traits::I2cBusDevice,
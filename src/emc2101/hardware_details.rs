@@ -4,7 +4,75 @@
 
 use crate::emc2101::hw;
 
-static UNKNOWN: &str = "<unknown>";
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+// ------------------------------------------------------------------------
+// product variants
+// ------------------------------------------------------------------------
+
+/// the supported product variants
+/// - EMC2101-R loads its configuration from an external EEPROM at power-up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+    Emc2101,
+    Emc2101R,
+}
+
+// SMSC
+const MANUFACTURER_ID: u8 = 0x5D;
+
+impl Variant {
+    /// identify the product variant based on the manufacturer and product ID
+    pub fn from_ids(mid: u8, pid: u8) -> Result<Variant, HardwareError> {
+        if mid != MANUFACTURER_ID {
+            return Err(HardwareError::UnknownManufacturer(mid));
+        }
+
+        // implicit return
+        Variant::from_product_id(pid)
+    }
+
+    /// identify the product variant based on the product ID only
+    pub fn from_product_id(pid: u8) -> Result<Variant, HardwareError> {
+        // implicit return
+        match pid {
+            0x16 => Ok(Variant::Emc2101),
+            0x28 => Ok(Variant::Emc2101R),
+            _ => Err(HardwareError::UnknownProduct(pid)),
+        }
+    }
+
+    /// returns 'true' if the variant loads its configuration from an EEPROM
+    pub fn has_eeprom(&self) -> bool {
+        // implicit return
+        *self == Variant::Emc2101R
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HardwareError {
+    /// the manufacturer ID is not SMSC (0x5D)
+    UnknownManufacturer(u8),
+    /// the product ID is neither EMC2101 (0x16) nor EMC2101-R (0x28)
+    UnknownProduct(u8),
+    /// the requested functionality is not available on this variant
+    UnsupportedVariant(Variant),
+    /// the device did not finish its power-up sequence in time
+    Timeout,
+}
+
+/// identify the product variant
+pub fn get_variant<Ibd>(ibd: &mut Ibd) -> Result<Variant, HardwareError>
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let mid = hw::get_manufacturer_id(ibd);
+    let pid = hw::get_product_id(ibd);
+
+    // implicit return
+    Variant::from_ids(mid, pid)
+}
 
 // ------------------------------------------------------------------------
 // hardware details
@@ -17,24 +85,30 @@ pub struct HardwareDetails {
     pub pid: u8,
     pub product: &'static str,
     pub revision: u8,
+    pub variant: Variant,
 }
 
 /// read the hardware details
+/// - returns an error if the manufacturer or product is unknown
 ///
 /// usage:
 /// ```TEXT
 ///
 /// // <initialize an I²C bus object>
 ///
-/// let hwd = i2c_devices::emc2101::get_hardware_details(&mut ibd0);
-/// // concise, e.g. "SMSC EMC2101 (rev: 1)"
-/// info!("{0} {1} (rev: {2})", hwd.manufacturer, hwd.product, hwd.prv);
-/// // detailed
-/// info!("Manufacturer: {0} ({1:#04X})", hwd.manufacturer, hwd.mid);
-/// info!("Product:      {0} ({1:#04X})", hwd.product, hwd.pid);
-/// info!("Revision:     {0:#04X}", hwd.revision);
+/// match i2c_devices::emc2101::get_hardware_details(&mut ibd0) {
+///     Ok(hwd) => {
+///         // concise, e.g. "SMSC EMC2101 (rev: 1)"
+///         info!("{0} {1} (rev: {2})", hwd.manufacturer, hwd.product, hwd.revision);
+///         // detailed
+///         info!("Manufacturer: {0} ({1:#04X})", hwd.manufacturer, hwd.mid);
+///         info!("Product:      {0} ({1:#04X})", hwd.product, hwd.pid);
+///         info!("Revision:     {0:#04X}", hwd.revision);
+///     }
+///     Err(e) => error!("Unsupported device: {e:?}"),
+/// }
 /// ```
-pub fn get_hardware_details<Ibd>(ibd: &mut Ibd) -> HardwareDetails
+pub fn get_hardware_details<Ibd>(ibd: &mut Ibd) -> Result<HardwareDetails, HardwareError>
 where
     Ibd: crate::traits::I2cBusDevice,
 {
@@ -42,17 +116,21 @@ where
     let pid = hw::get_product_id(ibd);
     let rev = hw::get_product_revision(ibd);
 
-    let man = identify_manufacturer(mid);
-    let prd = identify_product(pid);
+    let variant = Variant::from_ids(mid, pid)?;
+    let product = match variant {
+        Variant::Emc2101 => "EMC2101",
+        Variant::Emc2101R => "EMC2101-R",
+    };
 
     // implicit return
-    HardwareDetails {
+    Ok(HardwareDetails {
         mid,
-        manufacturer: man,
+        manufacturer: "SMSC",
         pid,
-        product: prd,
+        product,
         revision: rev,
-    }
+        variant,
+    })
 }
 
 /// a representation of the EMC2101's status register (0x02)
@@ -63,7 +141,7 @@ pub struct StatusRegister {
     // the comment describes what happens if the value is set to True
     pub busy: bool,        // ADC is converting
     pub temp_int_hi: bool, // internal temperature has met or exceeded the high limit
-    pub eeprom: bool,      // EEPROM  could  not  be  found (EMC2101-R)
+    pub eeprom: bool,      // EEPROM could not be found (EMC2101-R only)
     pub temp_ext_hi: bool, // external diode temperature has exceeded the high limit
     pub temp_ext_lo: bool, // external diode temperature has fallen below the low limit
     pub diode_fault: bool, // fault has occurred on the External Diode
@@ -91,27 +169,92 @@ where
 }
 
 // ------------------------------------------------------------------------
-// helper functions
+// EEPROM (EMC2101-R only)
 // ------------------------------------------------------------------------
 
-fn identify_manufacturer(mid: u8) -> &'static str {
-    let smsc: &'static str = "SMSC";
+/// the result of loading the configuration from the EEPROM at power-up
+#[derive(Debug, PartialEq)]
+pub enum EepromStatus {
+    Loaded,   // configuration was loaded from the EEPROM
+    NotFound, // EEPROM could not be found, default values are used
+}
+
+/// read the EEPROM status (EMC2101-R only)
+/// - the EMC2101 does not support an EEPROM and an error is returned
+/// - the status register is read, this clears its latched alert flags
+pub fn get_eeprom_status<Ibd>(ibd: &mut Ibd) -> Result<EepromStatus, HardwareError>
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let variant = get_variant(ibd)?;
+    if !variant.has_eeprom() {
+        return Err(HardwareError::UnsupportedVariant(variant));
+    }
 
     // implicit return
-    match mid {
-        0x5D => smsc,
-        _ => UNKNOWN,
+    if get_status_register(ibd).eeprom {
+        Ok(EepromStatus::NotFound)
+    } else {
+        Ok(EepromStatus::Loaded)
     }
 }
 
-fn identify_product(pid: u8) -> &'static str {
-    let emc2101: &'static str = "EMC2101";
-    let emc2101r: &'static str = "EMC2101-R";
+/// wait for the EMC2101-R to load its configuration from the EEPROM
+/// - the caller polls the device (e.g. once per millisecond), nothing
+///   sleeps or blocks
+/// - the number of polls is bounded by 'max_polls'
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut power_up = PowerUp::new(50);
+/// let variant = loop {
+///     if let Some(variant) = power_up.poll(&mut ibd)? {
+///         break variant;
+///     }
+///     ibd.sleep_ms(1);
+/// };
+/// ```
+///
+/// Do not call 'reset_device_registers()' or 'validate_device_registers()'
+/// on an EMC2101-R which loaded its configuration from the EEPROM. The
+/// loaded configuration would be overwritten or reported as invalid.
+#[derive(Debug, PartialEq)]
+pub struct PowerUp {
+    pub max_polls: u32, // the number of polls before giving up
+    polls: u32,         // the number of polls so far
+}
 
-    // implicit return
-    match pid {
-        0x16 => emc2101,
-        0x28 => emc2101r,
-        _ => UNKNOWN,
+impl PowerUp {
+    pub fn new(max_polls: u32) -> Self {
+        PowerUp {
+            max_polls,
+            polls: 0,
+        }
+    }
+
+    /// check once if the power-up sequence has finished
+    /// - returns the variant once the device is ready ('None' while busy)
+    /// - EMC2101: ready immediately (no EEPROM)
+    /// - EMC2101-R: ready once the busy bit of the status register is
+    ///   cleared (reading the status register clears its alert flags)
+    /// - returns an error if the device is still busy after 'max_polls'
+    pub fn poll<Ibd>(&mut self, ibd: &mut Ibd) -> Result<Option<Variant>, HardwareError>
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let variant = get_variant(ibd)?;
+        if !variant.has_eeprom() || !get_status_register(ibd).busy {
+            return Ok(Some(variant));
+        }
+
+        self.polls = self.polls.saturating_add(1);
+        if self.polls >= self.max_polls {
+            warn!("Device is still busy after {0} polls.", self.polls);
+            return Err(HardwareError::Timeout);
+        }
+
+        // implicit return
+        Ok(None)
     }
 }
@@ -4,29 +4,33 @@
 //
 
 pub enum DR {
-    Its = 0x00,       // internal sensor - temperature
-    EtsMsb = 0x01,    // external diode - temperature (high byte)
-    Status = 0x02,    // status
-    Cfg = 0x03,       // device config register
-    ConvRate = 0x04,  // conversion rate
-    ItsHi = 0x05,     // internal sensor - temperature high limit
-    EtsHiMsb = 0x07,  // external diode - temperature high limit (high byte)
-    EtsLoMsb = 0x08,  // external diode - temperature low limit (high byte)
-    EtsFrc = 0x0C,    // force external temperature reading
-    OneShot = 0x0F,   // one shot
-    EtsLsb = 0x10,    // external temperature sensor (low byte)
-    Scratch1 = 0x11,  // scratchpad #1
-    Scratch2 = 0x12,  // scratchpad #2
-    EtsHiLsb = 0x13,  // external diode - temperature high limit (low byte)
-    EtsLoLsb = 0x14,  // external diode - temperature low limit (low byte)
-    AlrtMsk = 0x16,   // alert mask
-    EtsDif = 0x17,    // external diode - ideality factor
-    EtsBcf = 0x18,    // external diode - beta compensation factor
-    CritTemp = 0x19,  // critical temperature limit
-    CritHyst = 0x21,  // critical temperature hysteresis
-    TachLsb = 0x46,   // tach reading (low byte)
-    TachMsb = 0x47,   // tach reading (high byte)
+    Its = 0x00,      // internal sensor - temperature
+    EtsMsb = 0x01,   // external diode - temperature (high byte)
+    Status = 0x02,   // status
+    Cfg = 0x03,      // device config register
+    ConvRate = 0x04, // conversion rate
+    ItsHi = 0x05,    // internal sensor - temperature high limit
+    EtsHiMsb = 0x07, // external diode - temperature high limit (high byte)
+    EtsLoMsb = 0x08, // external diode - temperature low limit (high byte)
+    EtsFrc = 0x0C,   // force external temperature reading
+    OneShot = 0x0F,  // one shot
+    EtsLsb = 0x10,   // external temperature sensor (low byte)
+    Scratch1 = 0x11, // scratchpad #1
+    Scratch2 = 0x12, // scratchpad #2
+    EtsHiLsb = 0x13, // external diode - temperature high limit (low byte)
+    EtsLoLsb = 0x14, // external diode - temperature low limit (low byte)
+    AlrtMsk = 0x16,  // alert mask
+    EtsDif = 0x17,   // external diode - ideality factor
+    EtsBcf = 0x18,   // external diode - beta compensation factor
+    CritTemp = 0x19, // critical temperature limit
+    CritHyst = 0x21, // critical temperature hysteresis
+    TachLsb = 0x46,  // tach reading (low byte)
+    // only read as the second byte of a block access (after TachLsb)
+    #[allow(dead_code)]
+    TachMsb = 0x47, // tach reading (high byte)
     TachLoLsb = 0x48, // rpm low limit (low byte)
+    // only accessed as the second byte of a block access (after TachLoLsb)
+    #[allow(dead_code)]
     TachLoMsb = 0x49, // rpm low limit (high byte)
     FanCfg = 0x4A,    // fan configuration
     FanSpinUp = 0x4B, // fan spin-up configuration
@@ -7,10 +7,11 @@
 
 pub mod defaults;
 mod device_registers;
+pub mod register_map;
 
 use core::cmp::Ord;
 use core::iter::Iterator;
-use device_registers::DR;
+pub(crate) use device_registers::DR;
 
 #[allow(unused_imports)]
 use log::{debug, error, info, warn};
@@ -68,40 +69,80 @@ where
 }
 
 /// reset all R/W registers to their default values
+/// - consecutive registers are written in a single transaction
 pub fn reset_device_registers<Ibd>(ibd: &mut Ibd)
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    // TODO perform a single write transaction
-    for data in DEFAULTS.iter() {
-        let register = data[0];
-        let default = data[1];
-        ibd.write_register_as_byte(DEVICE_ADDRESS, register, default);
+    let mut idx = 0;
+    while idx < DEFAULTS.len() {
+        let run = &DEFAULTS[idx..idx + count_consecutive_registers(&DEFAULTS[idx..])];
+
+        let mut values = [0x00; DEFAULTS.len()];
+        for (value, data) in values.iter_mut().zip(run.iter()) {
+            *value = data[1];
+        }
+        ibd.write_register_block(DEVICE_ADDRESS, run[0][0], &values[..run.len()]);
+
+        idx += run.len();
     }
 }
 
 /// validate that the R/W registers are set to their default values
 /// (this function can be used to verify the hardware is working)
+/// - consecutive registers are read in a single transaction
 pub fn validate_device_registers<Ibd>(ibd: &mut Ibd) -> bool
 where
     Ibd: crate::traits::I2cBusDevice,
 {
     let mut is_ok = true;
-    for data in DEFAULTS.iter() {
-        let register = data[0];
-        let default = data[1];
-
-        let value = ibd.read_register_as_byte(DEVICE_ADDRESS, register);
-        if default != value {
-            warn!("Currently stored and default value for register '{register:#04X}' do not match: {default:#04X} != {value:#04X}");
-            is_ok = false;
+
+    let mut idx = 0;
+    while idx < DEFAULTS.len() {
+        let run = &DEFAULTS[idx..idx + count_consecutive_registers(&DEFAULTS[idx..])];
+
+        let mut values = [0x00; DEFAULTS.len()];
+        ibd.read_register_block(DEVICE_ADDRESS, run[0][0], &mut values[..run.len()]);
+
+        for (value, data) in values.iter().zip(run.iter()) {
+            let register = data[0];
+            let default = data[1];
+            if default != *value {
+                warn!("Currently stored and default value for register '{register:#04X}' do not match: {default:#04X} != {value:#04X}");
+                is_ok = false;
+            }
         }
+
+        idx += run.len();
     }
 
     // implicit return
     is_ok
 }
 
+/// read consecutive registers, starting with device register 'dr'
+/// (the registers' values are stored in the provided buffer)
+///
+/// no interpretation is performed, refer to 'register_map' for a
+/// description of the individual registers
+pub fn read_register_block<Ibd>(ibd: &mut Ibd, dr: u8, buffer: &mut [u8])
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    ibd.read_register_block(DEVICE_ADDRESS, dr, buffer);
+}
+
+/// write consecutive registers, starting with device register 'dr'
+///
+/// no validation is performed, refer to 'register_map' for a
+/// description of the individual registers
+pub fn write_register_block<Ibd>(ibd: &mut Ibd, dr: u8, bytes: &[u8])
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    ibd.write_register_block(DEVICE_ADDRESS, dr, bytes);
+}
+
 /// get the device's status register
 ///
 /// default: 0b0000_0000
@@ -209,11 +250,9 @@ pub fn get_tach_reading<Ibd>(ibd: &mut Ibd) -> u16
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    let adr = [
-        DR::TachLsb as u8, // low byte, must be read first!
-        DR::TachMsb as u8, // high byte
-    ];
-    let values = ibd.read_multibyte_register_as_u8(DEVICE_ADDRESS, adr);
+    // low byte (0x46) must be read first, high byte (0x47) follows
+    let mut values = [0x00; 2];
+    ibd.read_register_block(DEVICE_ADDRESS, DR::TachLsb as u8, &mut values);
     debug!("tach (bytes): {0:#04X} {1:#04X}", values[0], values[1]);
 
     // implicit return
@@ -225,11 +264,9 @@ pub fn get_tach_limit<Ibd>(ibd: &mut Ibd) -> u16
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    let adr = [
-        DR::TachLoLsb as u8, // low byte, must be read first!
-        DR::TachLoMsb as u8, // high byte
-    ];
-    let values = ibd.read_multibyte_register_as_u8(DEVICE_ADDRESS, adr);
+    // low byte (0x48) must be read first, high byte (0x49) follows
+    let mut values = [0x00; 2];
+    ibd.read_register_block(DEVICE_ADDRESS, DR::TachLoLsb as u8, &mut values);
 
     // implicit return
     u16::from_le_bytes(values)
@@ -240,19 +277,13 @@ pub fn set_tach_limit<Ibd>(ibd: &mut Ibd, tach: u16)
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    let lsb = (tach & 0b1111_1111) as u8;
-    let msb = ((tach >> 8) & 0b1111_1111) as u8;
-
-    let values = [
-        [DR::TachLoLsb as u8, lsb], // low byte
-        [DR::TachLoMsb as u8, msb], // high byte
-    ];
-    ibd.write_multibyte_register_as_u8(DEVICE_ADDRESS, values);
+    // low byte (0x48) followed by high byte (0x49)
+    ibd.write_register_block(DEVICE_ADDRESS, DR::TachLoLsb as u8, &tach.to_le_bytes());
 }
 
 /// read the fan config register
 ///
-/// expected range: 0..31
+/// expected range: 0..127 (bit 7 is unused)
 pub fn get_fan_config<Ibd>(ibd: &mut Ibd) -> u8
 where
     Ibd: crate::traits::I2cBusDevice,
@@ -263,13 +294,13 @@ where
 
 /// change the fan config register
 ///
-/// expected range: 0..31
+/// expected range: 0..127 (bit 7 is unused and always written as 0)
 pub fn set_fan_config<Ibd>(ibd: &mut Ibd, value: u8)
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    let value_clamped = value.clamp(0, 31);
-    ibd.write_register_as_byte(DEVICE_ADDRESS, DR::FanCfg as u8, value_clamped);
+    let value_masked = value & 0b0111_1111;
+    ibd.write_register_as_byte(DEVICE_ADDRESS, DR::FanCfg as u8, value_masked);
 }
 
 /// read the fan spin up behavior register
@@ -764,20 +795,21 @@ where
 }
 
 /// read the lookup table registers
+/// - all 16 registers (0x50..0x5F) are read in a single transaction
 ///
 /// (see data sheet section 6.22 for details)
 pub fn get_lookup_table<Ibd>(ibd: &mut Ibd) -> [(u8, u8); 8]
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    let mut lut = [(0x00, 0x00); 8];
+    let mut values = [0x00; 16];
+    ibd.read_register_block(DEVICE_ADDRESS, DR::LutBase as u8, &mut values);
 
-    // convert the tuple pairs into consecutive read requests
-    let adr = DR::LutBase as u8;
+    // convert the consecutive registers into tuple pairs
+    let mut lut = [(0x00, 0x00); 8];
     for (i, value) in lut.iter_mut().enumerate() {
-        let offset = (i as u8) * 2; // 0, 2, 4, .. 14
-        value.0 = ibd.read_register_as_byte(DEVICE_ADDRESS, adr + offset);
-        value.1 = ibd.read_register_as_byte(DEVICE_ADDRESS, adr + offset + 1);
+        let offset = i * 2; // 0, 2, 4, .. 14
+        *value = (values[offset], values[offset + 1]);
     }
 
     // implicit return
@@ -785,17 +817,46 @@ where
 }
 
 /// change the lookup table registers
+/// - all 16 registers (0x50..0x5F) are written in a single transaction
 ///
 /// (see data sheet section 6.22 for details)
 pub fn set_lookup_table<Ibd>(ibd: &mut Ibd, lut: [(u8, u8); 8])
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    // convert consecutive read requests into the tuple pairs
-    let adr = DR::LutBase as u8;
+    // convert the tuple pairs into consecutive registers
+    let mut values = [0x00; 16];
     for (i, value) in lut.iter().enumerate() {
-        let offset = (i as u8) * 2; // 0, 2, 4, .. 14
-        ibd.write_register_as_byte(DEVICE_ADDRESS, adr + offset, value.0);
-        ibd.write_register_as_byte(DEVICE_ADDRESS, adr + offset + 1, value.1);
+        let offset = i * 2; // 0, 2, 4, .. 14
+        values[offset] = value.0;
+        values[offset + 1] = value.1;
     }
+
+    ibd.write_register_block(DEVICE_ADDRESS, DR::LutBase as u8, &values);
+}
+
+// ------------------------------------------------------------------------
+// helper functions
+// ------------------------------------------------------------------------
+
+/// count the number of consecutive registers at the start of the provided
+/// list (e.g. 0x48, 0x49, 0x4A, 0x4C -> 3)
+fn count_consecutive_registers(registers: &[[u8; 2]]) -> usize {
+    let mut count = 1;
+    while count < registers.len() && registers[count][0] == registers[count - 1][0] + 1 {
+        count += 1;
+    }
+
+    // implicit return
+    count
+}
+
+#[test]
+fn count_consecutive_registers_pass() {
+    assert_eq!(count_consecutive_registers(&[[0x03, 0x00]]), 1);
+    assert_eq!(
+        count_consecutive_registers(&[[0x03, 0x00], [0x04, 0x08], [0x05, 0x46], [0x07, 0x46]]),
+        3
+    );
+    assert_eq!(count_consecutive_registers(&DEFAULTS[16..]), 24); // 0x48..0x5F
 }
@@ -0,0 +1,234 @@
+//
+// register map
+// (described in section 5 and 6 of the data sheet)
+//
+// a data-driven description of all documented registers, suitable for
+// dumping and decoding the device's state
+// - the alias registers (0x09..0x0E) are not listed
+// - the registers are listed in ascending order
+//
+
+/// the register's access mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Access {
+    ReadOnly,
+    ReadWrite,
+}
+
+/// a named group of bits within a register
+#[derive(Debug, PartialEq)]
+pub struct Bitfield {
+    pub name: &'static str,
+    pub mask: u8,
+}
+
+impl Bitfield {
+    /// extract this bitfield's value from the provided register value
+    /// (the value is shifted to start at bit 0)
+    pub fn extract(&self, value: u8) -> u8 {
+        // implicit return
+        (value & self.mask) >> self.mask.trailing_zeros()
+    }
+}
+
+/// a description of a single device register
+#[derive(Debug, PartialEq)]
+pub struct Register {
+    pub address: u8,
+    pub name: &'static str,
+    pub access: Access,
+    /// the value after power-up (read-only registers have no default)
+    pub default: Option<u8>,
+    /// the named bitfields (empty if the register stores a single value)
+    pub fields: &'static [Bitfield],
+}
+
+impl Register {
+    const fn with_fields(self, fields: &'static [Bitfield]) -> Self {
+        Register { fields, ..self }
+    }
+
+    /// find the register with the provided address
+    pub fn find(address: u8) -> Option<&'static Register> {
+        // implicit return
+        REGISTERS.iter().find(|r| r.address == address)
+    }
+}
+
+// ------------------------------------------------------------------------
+// bitfields
+// ------------------------------------------------------------------------
+
+static STATUS: [Bitfield; 8] = [
+    field("BUSY", 0b1000_0000),
+    field("INT_HIGH", 0b0100_0000),
+    field("EEPROM", 0b0010_0000),
+    field("EXT_HIGH", 0b0001_0000),
+    field("EXT_LOW", 0b0000_1000),
+    field("FAULT", 0b0000_0100),
+    field("TCRIT", 0b0000_0010),
+    field("TACH", 0b0000_0001),
+];
+
+static CONFIG: [Bitfield; 8] = [
+    field("MASK", 0b1000_0000),
+    field("STANDBY", 0b0100_0000),
+    field("FAN_STBY", 0b0010_0000),
+    field("DAC", 0b0001_0000),
+    field("DIS_TO", 0b0000_1000),
+    field("ALT_TCH", 0b0000_0100),
+    field("TCRIT_OVRD", 0b0000_0010),
+    field("QUEUE", 0b0000_0001),
+];
+
+static CONVERSION_RATE: [Bitfield; 1] = [field("CONV", 0b0000_1111)];
+
+static FRACTION: [Bitfield; 1] = [field("FRACTION", 0b1110_0000)];
+
+static ALERT_MASK: [Bitfield; 5] = [
+    field("INT_MSK", 0b0100_0000),
+    field("HIGH_MSK", 0b0001_0000),
+    field("LOW_MSK", 0b0000_1000),
+    field("TCRIT_MSK", 0b0000_0010),
+    field("TACH_MSK", 0b0000_0001),
+];
+
+static IDEALITY_FACTOR: [Bitfield; 1] = [field("IDEALITY", 0b0011_1111)];
+
+static BETA_COMPENSATION: [Bitfield; 2] = [field("AUTO", 0b0000_1000), field("BETA", 0b0000_0111)];
+
+static FAN_CONFIG: [Bitfield; 6] = [
+    field("FORCE", 0b0100_0000),
+    field("PROG", 0b0010_0000),
+    field("POLARITY", 0b0001_0000),
+    field("CLK_SEL", 0b0000_1000),
+    field("CLK_OVR", 0b0000_0100),
+    field("TACH_MODE", 0b0000_0011),
+];
+
+static FAN_SPIN_UP: [Bitfield; 3] = [
+    field("FAST", 0b0010_0000),
+    field("STRENGTH", 0b0001_1000),
+    field("DURATION", 0b0000_0111),
+];
+
+static FAN_SETTING: [Bitfield; 1] = [field("SETTING", 0b0011_1111)];
+
+static PWM_FREQUENCY: [Bitfield; 1] = [field("PWM_F", 0b0001_1111)];
+
+static LUT_HYSTERESIS: [Bitfield; 1] = [field("HYST", 0b0001_1111)];
+
+static AVERAGING_FILTER: [Bitfield; 2] = [
+    field("FILTER", 0b0000_0110),
+    field("ALERT_PIN", 0b0000_0001),
+];
+
+// ------------------------------------------------------------------------
+// registers
+// ------------------------------------------------------------------------
+
+// shorthands to keep the tables readable
+const fn field(name: &'static str, mask: u8) -> Bitfield {
+    Bitfield { name, mask }
+}
+
+const fn ro(address: u8, name: &'static str) -> Register {
+    Register {
+        address,
+        name,
+        access: Access::ReadOnly,
+        default: None,
+        fields: &[],
+    }
+}
+
+const fn rw(address: u8, name: &'static str, default: u8) -> Register {
+    Register {
+        address,
+        name,
+        access: Access::ReadWrite,
+        default: Some(default),
+        fields: &[],
+    }
+}
+
+pub static REGISTERS: [Register; 50] = [
+    ro(0x00, "Internal Temperature"),
+    ro(0x01, "External Diode Temperature High Byte"),
+    ro(0x02, "Status").with_fields(&STATUS),
+    rw(0x03, "Configuration", 0x00).with_fields(&CONFIG),
+    rw(0x04, "Conversion Rate", 0x08).with_fields(&CONVERSION_RATE),
+    rw(0x05, "Internal Temp Limit", 0x46),
+    rw(0x07, "External Temp High Limit High Byte", 0x46),
+    rw(0x08, "External Temp Low Limit High Byte", 0x00),
+    rw(0x0C, "External Temperature Force", 0x00),
+    rw(0x0F, "One Shot", 0x00),
+    ro(0x10, "External Diode Temperature Low Byte").with_fields(&FRACTION),
+    rw(0x11, "Scratchpad #1", 0x00),
+    rw(0x12, "Scratchpad #2", 0x00),
+    rw(0x13, "External Diode High Limit Low Byte", 0x00).with_fields(&FRACTION),
+    rw(0x14, "External Diode Low Limit Low Byte", 0x00).with_fields(&FRACTION),
+    rw(0x16, "Alert Mask", 0xA4).with_fields(&ALERT_MASK),
+    rw(0x17, "External Diode Ideality Factor", 0x12).with_fields(&IDEALITY_FACTOR),
+    rw(0x18, "Beta Compensation Factor", 0x08).with_fields(&BETA_COMPENSATION),
+    rw(0x19, "TCRIT Temp Limit", 0x55),
+    rw(0x21, "TCRIT Hysteresis", 0x0A),
+    ro(0x46, "TACH Reading Low Byte"),
+    ro(0x47, "TACH Reading High Byte"),
+    rw(0x48, "TACH Limit Low Byte", 0xFF),
+    rw(0x49, "TACH Limit High Byte", 0xFF),
+    rw(0x4A, "FAN Configuration", 0x20).with_fields(&FAN_CONFIG),
+    rw(0x4B, "Fan Spin-up", 0x3F).with_fields(&FAN_SPIN_UP),
+    rw(0x4C, "Fan Setting", 0x00).with_fields(&FAN_SETTING),
+    rw(0x4D, "PWM Frequency", 0x17).with_fields(&PWM_FREQUENCY),
+    rw(0x4E, "PWM Frequency Divider", 0x01),
+    rw(0x4F, "Lookup Table Hysteresis", 0x04).with_fields(&LUT_HYSTERESIS),
+    rw(0x50, "Lookup Table Temp Setting 1", 0x7F),
+    rw(0x51, "Lookup Table Fan Setting 1", 0x3F),
+    rw(0x52, "Lookup Table Temp Setting 2", 0x7F),
+    rw(0x53, "Lookup Table Fan Setting 2", 0x3F),
+    rw(0x54, "Lookup Table Temp Setting 3", 0x7F),
+    rw(0x55, "Lookup Table Fan Setting 3", 0x3F),
+    rw(0x56, "Lookup Table Temp Setting 4", 0x7F),
+    rw(0x57, "Lookup Table Fan Setting 4", 0x3F),
+    rw(0x58, "Lookup Table Temp Setting 5", 0x7F),
+    rw(0x59, "Lookup Table Fan Setting 5", 0x3F),
+    rw(0x5A, "Lookup Table Temp Setting 6", 0x7F),
+    rw(0x5B, "Lookup Table Fan Setting 6", 0x3F),
+    rw(0x5C, "Lookup Table Temp Setting 7", 0x7F),
+    rw(0x5D, "Lookup Table Fan Setting 7", 0x3F),
+    rw(0x5E, "Lookup Table Temp Setting 8", 0x7F),
+    rw(0x5F, "Lookup Table Fan Setting 8", 0x3F),
+    rw(0xBF, "Averaging Filter", 0x00).with_fields(&AVERAGING_FILTER),
+    ro(0xFD, "Product ID"),
+    ro(0xFE, "Manufacturer ID"),
+    ro(0xFF, "Revision Register"),
+];
+
+#[test]
+fn registers_are_sorted() {
+    for pair in REGISTERS.windows(2) {
+        assert!(pair[0].address < pair[1].address);
+    }
+}
+
+#[test]
+fn registers_match_defaults() {
+    use crate::emc2101::hw::defaults::DEFAULTS;
+
+    let mut defaults = REGISTERS
+        .iter()
+        .filter_map(|r| r.default.map(|default| [r.address, default]));
+
+    for expected in DEFAULTS.iter() {
+        assert_eq!(defaults.next().as_ref(), Some(expected));
+    }
+    assert_eq!(defaults.next(), None);
+}
+
+#[test]
+fn extract_bitfield() {
+    assert_eq!(FAN_SPIN_UP[1].extract(0b0011_1111), 0b11);
+    assert_eq!(FAN_SPIN_UP[2].extract(0b0011_1010), 0b010);
+    assert_eq!(STATUS[0].extract(0b1000_0000), 1);
+}
@@ -14,8 +14,11 @@ mod conversion_rate;
 mod fan;
 mod hardware_details;
 mod lut;
+mod register_dump;
 mod scratch_register;
+mod self_test;
 mod temperature;
+mod thermal_monitor;
 
 pub use config::*;
 pub use conversion_rate::*;
@@ -26,5 +29,8 @@ pub use data_objects::{
 pub use fan::*;
 pub use hardware_details::*;
 pub use lut::*;
+pub use register_dump::*;
 pub use scratch_register::*;
+pub use self_test::*;
 pub use temperature::*;
+pub use thermal_monitor::*;
@@ -0,0 +1,143 @@
+/*
+    Dump all readable registers and decode them into named fields.
+
+    This is the equivalent of 'i2cdump' but the output is annotated with
+    the register names and the values of the individual bitfields.
+*/
+
+use core::fmt;
+
+use crate::emc2101::hw::register_map::{Access, Register, REGISTERS};
+use crate::emc2101::hw::{self, DR};
+
+/// a snapshot of all readable registers
+/// (the values are stored in the same order as 'hw::register_map::REGISTERS')
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisterDump {
+    pub values: [u8; REGISTERS.len()],
+}
+
+impl RegisterDump {
+    /// get the stored value for the register with the provided address
+    pub fn get(&self, address: u8) -> Option<u8> {
+        // implicit return
+        REGISTERS
+            .iter()
+            .position(|r| r.address == address)
+            .map(|idx| self.values[idx])
+    }
+
+    /// iterate over all registers and their stored values
+    pub fn iter(&self) -> impl Iterator<Item = (&'static Register, u8)> + '_ {
+        // implicit return
+        REGISTERS.iter().zip(self.values.iter().copied())
+    }
+}
+
+/// read all readable registers
+/// - consecutive registers are read in a single transaction
+/// - reading the Status register (0x02) clears its latched alert flags,
+///   read the status first if the alerts are still needed
+///
+/// usage:
+/// ```TEXT
+/// let dump = i2c_devices::emc2101::dump_registers(&mut ibd);
+/// info!("{dump}");
+/// ```
+pub fn dump_registers<Ibd>(ibd: &mut Ibd) -> RegisterDump
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let mut values = [0x00; REGISTERS.len()];
+
+    let mut idx = 0;
+    while idx < REGISTERS.len() {
+        let len = count_consecutive_registers(idx, |_| true);
+        hw::read_register_block(ibd, REGISTERS[idx].address, &mut values[idx..idx + len]);
+
+        idx += len;
+    }
+
+    // implicit return
+    RegisterDump { values }
+}
+
+/// write the values stored in the snapshot back to the R/W registers
+/// - consecutive registers are written in a single transaction
+/// - the One Shot register (0x0F) is skipped (would trigger a conversion)
+/// - the fan configuration (0x4A) is written last since it may make the
+///   lookup table read-only
+pub fn restore_registers<Ibd>(ibd: &mut Ibd, dump: &RegisterDump)
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    fn is_restorable(register: &Register) -> bool {
+        register.access == Access::ReadWrite
+            && register.address != DR::OneShot as u8
+            && register.address != DR::FanCfg as u8
+    }
+
+    // make sure the lookup table is writeable (set PROG bit)
+    hw::set_fan_config(ibd, 0b0010_0000);
+
+    let mut idx = 0;
+    while idx < REGISTERS.len() {
+        if is_restorable(&REGISTERS[idx]) {
+            let len = count_consecutive_registers(idx, is_restorable);
+            hw::write_register_block(ibd, REGISTERS[idx].address, &dump.values[idx..idx + len]);
+            idx += len;
+        } else {
+            idx += 1;
+        }
+    }
+
+    if let Some(value) = dump.get(DR::FanCfg as u8) {
+        hw::set_fan_config(ibd, value);
+    }
+}
+
+// ------------------------------------------------------------------------
+// helper functions
+// ------------------------------------------------------------------------
+
+/// count the registers with consecutive addresses, starting at 'idx'
+/// (stops at the first register not matching the predicate)
+fn count_consecutive_registers(idx: usize, include: fn(&Register) -> bool) -> usize {
+    let mut len = 1;
+    while idx + len < REGISTERS.len()
+        && include(&REGISTERS[idx + len])
+        && REGISTERS[idx + len].address == REGISTERS[idx + len - 1].address + 1
+    {
+        len += 1;
+    }
+
+    // implicit return
+    len
+}
+
+/// one line per register, e.g.:
+///
+/// ```TEXT
+/// 0x03 R/W Configuration                        0x00 MASK=0 STANDBY=0 ...
+/// ```
+impl fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (register, value) in self.iter() {
+            let access = match register.access {
+                Access::ReadOnly => "R",
+                Access::ReadWrite => "R/W",
+            };
+            write!(
+                f,
+                "{0:#04X} {1:<3} {2:<36} {3:#04X}",
+                register.address, access, register.name, value
+            )?;
+            for field in register.fields.iter() {
+                write!(f, " {0}={1}", field.name, field.extract(value))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+/*
+    non-destructive hardware self-test
+
+    In contrast to 'validate_device_registers()' the self-test does not
+    require the device to be in its power-up state. The current register
+    state is saved before running the checks and restored afterwards.
+*/
+
+use crate::emc2101::hw;
+use crate::emc2101::{dump_registers, restore_registers, HardwareError, Variant};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+// bit patterns written to the scratch registers
+static SCRATCH_PATTERNS: [u8; 4] = [0x00, 0xFF, 0x55, 0xAA];
+
+// registers and their aliases (see data sheet section 6)
+static ALIASES: [(u8, u8); 5] = [
+    (0x03, 0x09), // Configuration
+    (0x04, 0x0A), // Conversion Rate
+    (0x05, 0x0B), // Internal Temp Limit
+    (0x07, 0x0D), // External Temp High Limit High Byte
+    (0x08, 0x0E), // External Temp Low Limit High Byte
+];
+
+// time to wait for the fan to settle after changing the fan speed
+static TACH_SETTLE_MS: u32 = 2_000;
+
+/// the result of the hardware self-test
+/// (each field is 'true' if the check passed)
+#[derive(Debug, PartialEq)]
+pub struct SelfTestReport {
+    pub manufacturer_id: bool, // manufacturer ID is SMSC
+    pub product_id: bool,      // product ID is EMC2101 or EMC2101-R
+    pub scratch: bool,         // scratch registers retain all test patterns
+    pub aliases: bool,         // alias registers mirror their counterparts
+    pub diode: bool,           // no external diode fault is reported
+    pub tach: bool,            // tach reading changes when the fan speed changes
+}
+
+impl SelfTestReport {
+    /// returns 'true' if all checks passed
+    pub fn passed(&self) -> bool {
+        // implicit return
+        self.manufacturer_id
+            && self.product_id
+            && self.scratch
+            && self.aliases
+            && self.diode
+            && self.tach
+    }
+}
+
+/// perform a non-destructive hardware self-test
+/// - all R/W registers are saved before and restored after the test
+/// - the fan is briefly stopped and then driven at full speed
+/// - this function will **sleep** for 4 seconds to let the fan settle
+///
+/// The tach check requires a 4-wire fan with the tach signal connected
+/// to pin 6 (ALERT/TACH).
+pub fn self_test<Ibd>(ibd: &mut Ibd) -> SelfTestReport
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let saved = dump_registers(ibd);
+
+    let (manufacturer_id, product_id) = check_ids(ibd);
+    let report = SelfTestReport {
+        manufacturer_id,
+        product_id,
+        scratch: check_scratch_registers(ibd),
+        aliases: check_alias_registers(ibd),
+        diode: check_diode(ibd),
+        tach: check_tach(ibd),
+    };
+
+    restore_registers(ibd, &saved);
+
+    // implicit return
+    report
+}
+
+// ------------------------------------------------------------------------
+// individual checks
+// ------------------------------------------------------------------------
+
+/// check the manufacturer and product ID (same rules as Variant::from_ids())
+fn check_ids<Ibd>(ibd: &mut Ibd) -> (bool, bool)
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let mid = hw::get_manufacturer_id(ibd);
+    let pid = hw::get_product_id(ibd);
+
+    let variant = Variant::from_ids(mid, pid);
+    if let Err(e) = &variant {
+        warn!("Unexpected device: {e:?}");
+    }
+    let manufacturer_id = !matches!(variant, Err(HardwareError::UnknownManufacturer(_)));
+    let product_id = Variant::from_product_id(pid).is_ok();
+
+    // implicit return
+    (manufacturer_id, product_id)
+}
+
+fn check_scratch_registers<Ibd>(ibd: &mut Ibd) -> bool
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let mut is_ok = true;
+    for pattern in SCRATCH_PATTERNS {
+        hw::set_scratch_register1(ibd, pattern);
+        hw::set_scratch_register2(ibd, !pattern);
+
+        let value1 = hw::get_scratch_register1(ibd);
+        let value2 = hw::get_scratch_register2(ibd);
+        if value1 != pattern || value2 != !pattern {
+            warn!("Scratch registers do not retain pattern {pattern:#04X}: {value1:#04X}, {value2:#04X}");
+            is_ok = false;
+        }
+    }
+
+    // implicit return
+    is_ok
+}
+
+fn check_alias_registers<Ibd>(ibd: &mut Ibd) -> bool
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let mut is_ok = true;
+    for (register, alias) in ALIASES {
+        let mut value = [0x00];
+        let mut alias_value = [0x00];
+        hw::read_register_block(ibd, register, &mut value);
+        hw::read_register_block(ibd, alias, &mut alias_value);
+        if value != alias_value {
+            warn!("Alias register {alias:#04X} does not match register {register:#04X}: {0:#04X} != {1:#04X}", alias_value[0], value[0]);
+            is_ok = false;
+        }
+    }
+
+    // implicit return
+    is_ok
+}
+
+fn check_diode<Ibd>(ibd: &mut Ibd) -> bool
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let status = hw::get_status_register(ibd);
+    let is_faulty = (status & 0b0000_0100) != 0;
+    if is_faulty {
+        warn!("External diode fault detected.");
+    }
+
+    // implicit return
+    !is_faulty
+}
+
+fn check_tach<Ibd>(ibd: &mut Ibd) -> bool
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    // configure pin 6 as tach input and use the fan setting register
+    // (disables the lookup table)
+    hw::set_config_register(ibd, 0b0000_0100);
+    hw::set_fan_config(ibd, 0b0010_0000);
+
+    hw::set_fan_speed(ibd, 0x00);
+    ibd.sleep_ms(TACH_SETTLE_MS);
+    let tach_min = hw::get_tach_reading(ibd);
+
+    hw::set_fan_speed(ibd, 0x3F);
+    ibd.sleep_ms(TACH_SETTLE_MS);
+    let tach_max = hw::get_tach_reading(ibd);
+
+    debug!("tach reading: {tach_min} (stopped), {tach_max} (full speed)");
+    if tach_min == tach_max {
+        warn!("Tach reading does not change with the fan speed: {tach_min}");
+    }
+
+    // implicit return
+    tach_min != tach_max
+}
@@ -0,0 +1,260 @@
+/*
+    long-term thermal statistics
+
+    The monitor is fed by periodic measurements and keeps its statistics
+    in fixed memory. Mean and variance are computed incrementally using
+    Welford's algorithm. No samples are stored.
+*/
+
+use crate::emc2101::{
+    get_external_temperature, get_external_temperature_high_limit, get_internal_temperature,
+    get_rpm, get_status_register, ExternalDiodeStatus,
+};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+// ------------------------------------------------------------------------
+// running statistics
+// ------------------------------------------------------------------------
+
+/// summary of a single measurement
+/// - all values are 0.0 if no sample was recorded
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub count: u32,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub variance: f32, // population variance
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RunningStatistics {
+    count: u32,
+    min: f32,
+    max: f32,
+    mean: f32,
+    m2: f32, // sum of squared differences from the mean
+}
+
+impl RunningStatistics {
+    const fn new() -> Self {
+        RunningStatistics {
+            count: 0,
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    fn record(&mut self, value: f32) {
+        self.count = self.count.saturating_add(1);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f32;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn summary(&self) -> Summary {
+        if self.count == 0 {
+            return Summary {
+                count: 0,
+                min: 0.0,
+                max: 0.0,
+                mean: 0.0,
+                variance: 0.0,
+            };
+        }
+
+        // implicit return
+        Summary {
+            count: self.count,
+            min: self.min,
+            max: self.max,
+            mean: self.mean,
+            variance: self.m2 / self.count as f32,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+// thermal monitor
+// ------------------------------------------------------------------------
+
+/// a snapshot of the collected statistics
+/// (can be logged or serialized)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThermalSnapshot {
+    pub internal: Summary,        // internal temperature (in °C)
+    pub external: Summary,        // external temperature (in °C)
+    pub rpm: Summary,             // fan speed (in RPM)
+    pub time_above_limit_ms: u64, // time the external temperature exceeded the high limit
+    pub diode_faults: u32,        // number of times a diode fault occurred
+    pub rpm_low_events: u32,      // number of times the RPM dropped below the minimum
+}
+
+/// collect min, max, mean and variance of the measurements
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut monitor = ThermalMonitor::new();
+/// loop {
+///     monitor.sample(&mut ibd0, 1_000);
+///     ibd0.sleep_ms(1_000);
+/// }
+/// info!("{:?}", monitor.snapshot());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ThermalMonitor {
+    internal: RunningStatistics,
+    external: RunningStatistics,
+    rpm: RunningStatistics,
+    time_above_limit_ms: u64,
+    diode_faults: u32,
+    rpm_low_events: u32,
+    // previous state, used to count events instead of samples
+    is_diode_faulty: bool,
+    is_rpm_low: bool,
+}
+
+impl Default for ThermalMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThermalMonitor {
+    pub const fn new() -> Self {
+        ThermalMonitor {
+            internal: RunningStatistics::new(),
+            external: RunningStatistics::new(),
+            rpm: RunningStatistics::new(),
+            time_above_limit_ms: 0,
+            diode_faults: 0,
+            rpm_low_events: 0,
+            is_diode_faulty: false,
+            is_rpm_low: false,
+        }
+    }
+
+    /// discard all collected statistics
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// record an internal temperature measurement (in °C)
+    pub fn record_internal_temperature(&mut self, temperature: f32) {
+        self.internal.record(temperature);
+    }
+
+    /// record an external temperature measurement (in °C)
+    /// - 'elapsed_ms' is the time since the previous measurement and is
+    ///   added to the time above the limit if 'temperature' exceeds it
+    /// - measurements with a diode fault are counted but not recorded
+    pub fn record_external_temperature(
+        &mut self,
+        temperature: f32,
+        status: ExternalDiodeStatus,
+        high_limit: f32,
+        elapsed_ms: u32,
+    ) {
+        let is_faulty = status != ExternalDiodeStatus::Operational;
+        if is_faulty && !self.is_diode_faulty {
+            debug!("Diode fault detected: {status:?}");
+            self.diode_faults = self.diode_faults.saturating_add(1);
+        }
+        self.is_diode_faulty = is_faulty;
+        if is_faulty {
+            return;
+        }
+
+        self.external.record(temperature);
+        if temperature > high_limit {
+            self.time_above_limit_ms = self.time_above_limit_ms.saturating_add(elapsed_ms.into());
+        }
+    }
+
+    /// record a fan speed measurement (in RPM)
+    /// - 'rpm_low' is the status register's flag and counted as an event
+    ///   when it changes from 'false' to 'true'
+    pub fn record_rpm(&mut self, rpm: u32, rpm_low: bool) {
+        if rpm_low && !self.is_rpm_low {
+            debug!("RPM dropped below the minimum: {rpm}");
+            self.rpm_low_events = self.rpm_low_events.saturating_add(1);
+        }
+        self.is_rpm_low = rpm_low;
+
+        self.rpm.record(rpm as f32);
+    }
+
+    /// read all measurements from the device and record them
+    /// - 'elapsed_ms' is the time since the previous call
+    /// - reading the status register clears its flags
+    pub fn sample<Ibd>(&mut self, ibd: &mut Ibd, elapsed_ms: u32)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let internal = get_internal_temperature(ibd);
+        let (external, status) = get_external_temperature(ibd);
+        let high_limit = get_external_temperature_high_limit(ibd);
+        let rpm = get_rpm(ibd);
+        let rpm_low = get_status_register(ibd).rpm_low;
+
+        self.record_internal_temperature(internal);
+        self.record_external_temperature(external, status, high_limit, elapsed_ms);
+        self.record_rpm(rpm, rpm_low);
+    }
+
+    /// get the collected statistics
+    pub fn snapshot(&self) -> ThermalSnapshot {
+        // implicit return
+        ThermalSnapshot {
+            internal: self.internal.summary(),
+            external: self.external.summary(),
+            rpm: self.rpm.summary(),
+            time_above_limit_ms: self.time_above_limit_ms,
+            diode_faults: self.diode_faults,
+            rpm_low_events: self.rpm_low_events,
+        }
+    }
+}
+
+#[test]
+fn running_statistics() {
+    let mut stats = RunningStatistics::new();
+    for value in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+        stats.record(value);
+    }
+
+    let computed = stats.summary();
+    let expected = Summary {
+        count: 8,
+        min: 2.0,
+        max: 9.0,
+        mean: 5.0,
+        variance: 4.0,
+    };
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn running_statistics_empty() {
+    let stats = RunningStatistics::new();
+
+    let computed = stats.summary();
+    let expected = Summary {
+        count: 0,
+        min: 0.0,
+        max: 0.0,
+        mean: 0.0,
+        variance: 0.0,
+    };
+
+    assert_eq!(computed, expected);
+}
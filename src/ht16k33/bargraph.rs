@@ -0,0 +1,288 @@
+/*
+    bicolor bar graph with 24 bars (Adafruit bar graph backpack)
+
+    Each bar has a red and a green LED, yellow is red + green. The bars are
+    numbered from 0 to 23, bar 0 is next to the backpack's connector.
+
+    The bars are wired in 3 groups (common lines 0-2) of 4 bars for the
+    lower and 4 bars for the upper half:
+    - bar 0-11: com = bar / 4, row = bar % 4
+    - bar 12-23: com = (bar - 12) / 4, row = bar % 4 + 4
+    - red is row (byte 2×com), green is row + 8 (byte 2×com + 1)
+
+    Nothing is sent to the device until flush() or show_level() is called.
+*/
+
+use crate::ht16k33::hw::Dimming;
+use crate::ht16k33::{
+    BlinkRate, ConfigurationError, DeviceId, DisplayControl, LedColor, LedDisplay,
+};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the number of bars
+pub const BAR_COUNT: usize = 24;
+
+/// the bars where the color of a level switches
+/// - bars below 'yellow' are green
+/// - bars below 'red' are yellow
+/// - all other bars are red
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BarThresholds {
+    pub yellow: usize,
+    pub red: usize,
+}
+
+impl BarThresholds {
+    /// the color of the provided bar
+    pub fn get_color(&self, bar: usize) -> LedColor {
+        if bar < self.yellow {
+            LedColor::Green
+        } else if bar < self.red {
+            LedColor::Yellow
+        } else {
+            LedColor::Red
+        }
+    }
+}
+
+impl Default for BarThresholds {
+    /// 16 green, 4 yellow and 4 red bars
+    fn default() -> Self {
+        BarThresholds {
+            yellow: 16,
+            red: 20,
+        }
+    }
+}
+
+/// 24 bicolor bars
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut bar = BarGraph24::new(0)?;
+/// // keep the peak for 10 updates
+/// bar.peak_hold = Some(10);
+/// bar.show_level(&mut ibd, rpm, 2400);
+/// ```
+pub struct BarGraph24 {
+    pub did: DeviceId,
+    pub thresholds: BarThresholds,
+    pub peak_hold: Option<u32>, // the number of updates the peak is shown for
+    peak: Option<(usize, u32)>, // the peak bar & remaining updates
+    buffer: [u8; 16],
+    control: DisplayControl, // display mode, brightness level & what was sent to the device
+}
+
+impl BarGraph24 {
+    /// create a new bar graph (device ID must be in range 0 ≤ x ≤ 7)
+    pub fn new(did: u8) -> Result<Self, ConfigurationError> {
+        let bar_graph = BarGraph24 {
+            did: DeviceId::try_from(did)?,
+            thresholds: BarThresholds::default(),
+            peak_hold: None,
+            peak: None,
+            buffer: [0x00; 16],
+            control: DisplayControl::new(BlinkRate::NoBlink, Dimming::Duty16),
+        };
+
+        // implicit return
+        Ok(bar_graph)
+    }
+
+    /// the raw buffer (as sent to the display)
+    pub fn get_buffer(&self) -> &[u8; 16] {
+        &self.buffer
+    }
+
+    /// turn off all bars (and forget the peak)
+    pub fn clear(&mut self) {
+        self.buffer = [0x00; 16];
+        self.peak = None;
+    }
+
+    /// set all bars to the provided color
+    pub fn fill(&mut self, color: LedColor) {
+        for bar in 0..BAR_COUNT {
+            self.set_bar(bar, color);
+        }
+    }
+
+    /// set the color of a single bar
+    /// (bars outside of the display are silently ignored)
+    pub fn set_bar(&mut self, bar: usize, color: LedColor) {
+        if let Some((idx, mask)) = locate(bar) {
+            let (red, green) = match color {
+                LedColor::Off => (false, false),
+                LedColor::Green => (false, true),
+                LedColor::Red => (true, false),
+                LedColor::Yellow => (true, true),
+            };
+            set_bit(&mut self.buffer[idx], mask, red);
+            set_bit(&mut self.buffer[idx + 1], mask, green);
+        }
+    }
+
+    /// returns the bar's color
+    /// (bars outside of the display are always turned off)
+    pub fn get_bar(&self, bar: usize) -> LedColor {
+        let (idx, mask) = match locate(bar) {
+            Some(location) => location,
+            None => return LedColor::Off,
+        };
+
+        let red = self.buffer[idx] & mask != 0;
+        let green = self.buffer[idx + 1] & mask != 0;
+
+        // implicit return
+        match (green, red) {
+            (false, false) => LedColor::Off,
+            (true, false) => LedColor::Green,
+            (false, true) => LedColor::Red,
+            (true, true) => LedColor::Yellow,
+        }
+    }
+
+    /// display the provided value as a level (0 = no bar, max = all bars)
+    /// (deferred while the display is disabled)
+    /// - the bars are colored according to the thresholds
+    /// - values above max are shown as max
+    /// - the peak is shown for 'peak_hold' updates (if set)
+    pub fn show_level<Ibd>(&mut self, ibd: &mut Ibd, value: u32, max: u32)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let level = convert_to_bars(value, max);
+        self.update_peak(level);
+
+        for bar in 0..BAR_COUNT {
+            let color = if bar < level {
+                self.thresholds.get_color(bar)
+            } else {
+                LedColor::Off
+            };
+            self.set_bar(bar, color);
+        }
+        if let Some((peak, _)) = self.peak {
+            if peak > level {
+                self.set_bar(peak - 1, self.thresholds.get_color(peak - 1));
+            }
+        }
+
+        self.flush(ibd);
+    }
+
+    /// the number of bars of the peak ('None' if peak hold is disabled)
+    pub fn get_peak(&self) -> Option<usize> {
+        self.peak.map(|(peak, _)| peak)
+    }
+
+    // a higher level restarts the hold time, the peak falls back to the
+    // current level once the hold time expired
+    fn update_peak(&mut self, level: usize) {
+        let hold = match self.peak_hold {
+            Some(hold) => hold,
+            None => {
+                self.peak = None;
+                return;
+            }
+        };
+
+        self.peak = match self.peak {
+            Some((peak, remaining)) if peak > level && remaining > 0 => Some((peak, remaining - 1)),
+            _ => Some((level, hold)),
+        };
+    }
+}
+
+impl LedDisplay for BarGraph24 {
+    fn get_did(&self) -> DeviceId {
+        self.did
+    }
+
+    fn control(&self) -> &DisplayControl {
+        &self.control
+    }
+
+    fn control_mut(&mut self) -> &mut DisplayControl {
+        &mut self.control
+    }
+
+    fn get_framebuffer(&self) -> Option<&[u8; 16]> {
+        Some(&self.buffer)
+    }
+}
+
+// ------------------------------------------------------------------------
+// helper functions
+// ------------------------------------------------------------------------
+
+fn set_bit(byte: &mut u8, mask: u8, is_set: bool) {
+    if is_set {
+        *byte |= mask;
+    } else {
+        *byte &= !mask;
+    }
+}
+
+/// find the red byte and bit which represent the bar
+/// (the green byte follows the red byte)
+fn locate(bar: usize) -> Option<(usize, u8)> {
+    if bar >= BAR_COUNT {
+        return None;
+    }
+
+    let (com, row) = if bar < 12 {
+        (bar / 4, bar % 4)
+    } else {
+        ((bar - 12) / 4, bar % 4 + 4)
+    };
+
+    // implicit return
+    Some((2 * com, 1 << row))
+}
+
+/// scale the value to the number of bars (rounded)
+fn convert_to_bars(value: u32, max: u32) -> usize {
+    if max == 0 {
+        return 0;
+    }
+
+    let value = value.min(max) as u64;
+    let max = max as u64;
+
+    // implicit return
+    ((value * BAR_COUNT as u64 + max / 2) / max) as usize
+}
+
+#[test]
+fn locate_pass() {
+    let computed = [locate(0), locate(5), locate(11), locate(12), locate(23)];
+    let expected = [
+        Some((0, 0b0000_0001)),
+        Some((2, 0b0000_0010)),
+        Some((4, 0b0000_1000)),
+        Some((0, 0b0001_0000)),
+        Some((4, 0b1000_0000)),
+    ];
+
+    assert_eq!(computed, expected);
+    assert_eq!(locate(24), None);
+}
+
+#[test]
+fn convert_to_bars_pass() {
+    let computed = [
+        convert_to_bars(0, 100),
+        convert_to_bars(2, 100),
+        convert_to_bars(50, 100),
+        convert_to_bars(100, 100),
+        convert_to_bars(500, 100),
+        convert_to_bars(1, 0),
+    ];
+    let expected = [0, 0, 12, 24, 24, 0];
+
+    assert_eq!(computed, expected);
+}
@@ -0,0 +1,189 @@
+/*
+    adapt the brightness level to the ambient light
+
+    The reading (lux or a raw sensor value) is provided by the caller,
+    e.g. from a light sensor read in the main loop. It is mapped to the
+    brightness level using a curve of (reading, level) points, values
+    between two points are interpolated linearly.
+
+    A level change is only accepted if it is still a change when the
+    reading is moved back by the hysteresis. This avoids flicker when
+    the reading is close to the border of two levels.
+*/
+
+use crate::ht16k33::hw::Dimming;
+use crate::ht16k33::SegmentedDisplay;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the maximum brightness level of the HT16K33
+const MAX_LEVEL: u8 = Dimming::Duty16.get_level();
+
+/// a curve for readings in lux (dark room to daylight)
+pub static LUX_CURVE: [(u32, u8); 5] = [(0, 0), (10, 2), (100, 6), (1_000, 12), (10_000, 15)];
+
+/// follow the ambient light
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut adaptive = AdaptiveBrightness::new(&LUX_CURVE, 5);
+/// loop {
+///     let lux = read_light_sensor();
+///     // only sends a command if the level changed
+///     adaptive.update(&mut sd, &mut ibd, lux);
+///     ibd.sleep_ms(500);
+/// }
+/// ```
+pub struct AdaptiveBrightness<'a> {
+    pub curve: &'a [(u32, u8)], // (reading, level), ordered by reading
+    pub hysteresis: u32,        // in units of the reading
+    level: Option<u8>,          // the level sent to the display
+}
+
+impl<'a> AdaptiveBrightness<'a> {
+    /// create a controller
+    /// - the curve must be ordered by reading
+    /// - levels above 15 are treated as 15
+    /// - the curve may be built at runtime (e.g. loaded from a configuration)
+    pub const fn new(curve: &'a [(u32, u8)], hysteresis: u32) -> Self {
+        AdaptiveBrightness {
+            curve,
+            hysteresis,
+            level: None,
+        }
+    }
+
+    /// the level sent to the display ('None' before the first update)
+    pub fn get_level(&self) -> Option<u8> {
+        self.level
+    }
+
+    /// forget the level, the next update sets the level unconditionally
+    /// (e.g. after the brightness level was changed by someone else)
+    pub fn reset(&mut self) {
+        self.level = None;
+    }
+
+    /// the level for the provided reading (including the hysteresis)
+    pub fn compute_level(&self, reading: u32) -> u8 {
+        let target = self.lookup(reading);
+        let current = match self.level {
+            Some(current) => current,
+            None => return target,
+        };
+
+        // implicit return
+        if target > current {
+            self.lookup(reading.saturating_sub(self.hysteresis))
+                .max(current)
+        } else if target < current {
+            self.lookup(reading.saturating_add(self.hysteresis))
+                .min(current)
+        } else {
+            current
+        }
+    }
+
+    /// adapt the display's brightness level to the provided reading
+    /// - set_brightness_level() is only called if the level changed
+    /// - returns the new level (or 'None' if nothing changed or the level
+    ///   was rejected by the display)
+    pub fn update<D, Ibd>(&mut self, display: &mut D, ibd: &mut Ibd, reading: u32) -> Option<u8>
+    where
+        D: SegmentedDisplay,
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let level = self.compute_level(reading);
+        if self.level == Some(level) {
+            return None;
+        }
+
+        debug!("Reading {reading}, changing brightness level to {level}.");
+        if !display.set_brightness_level(ibd, level) {
+            return None;
+        }
+        self.level = Some(level);
+
+        // implicit return
+        Some(level)
+    }
+
+    // map the reading using the curve (without hysteresis)
+    fn lookup(&self, reading: u32) -> u8 {
+        let (first, last) = match (self.curve.first(), self.curve.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return MAX_LEVEL,
+        };
+        if reading <= first.0 {
+            return first.1.min(MAX_LEVEL);
+        }
+        if reading >= last.0 {
+            return last.1.min(MAX_LEVEL);
+        }
+
+        let idx = self
+            .curve
+            .windows(2)
+            .position(|pair| reading < pair[1].0)
+            .unwrap_or(0);
+        let (x0, y0) = self.curve[idx];
+        let (x1, y1) = self.curve[idx + 1];
+
+        // implicit return
+        interpolate(x0, y0.min(MAX_LEVEL), x1, y1.min(MAX_LEVEL), reading)
+    }
+}
+
+/// linear interpolation between two points (rounded)
+fn interpolate(x0: u32, y0: u8, x1: u32, y1: u8, x: u32) -> u8 {
+    if x1 <= x0 {
+        return y1;
+    }
+
+    let dx = (x1 - x0) as i64;
+    let dy = y1 as i64 - y0 as i64;
+    let offset = (x - x0) as i64 * dy;
+    // round half away from zero
+    let step = (2 * offset + dx * offset.signum()) / (2 * dx);
+
+    // implicit return
+    (y0 as i64 + step) as u8
+}
+
+#[test]
+fn interpolate_pass() {
+    let computed = [
+        interpolate(0, 0, 10, 2, 0),
+        interpolate(0, 0, 10, 2, 5),
+        interpolate(0, 0, 10, 2, 7),
+        interpolate(0, 15, 100, 0, 50),
+        interpolate(10, 3, 10, 5, 10),
+    ];
+    let expected = [0, 1, 1, 7, 5];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn lookup_pass() {
+    let adaptive = AdaptiveBrightness::new(&LUX_CURVE, 0);
+    let computed = [
+        adaptive.lookup(0),
+        adaptive.lookup(10),
+        adaptive.lookup(55),
+        adaptive.lookup(5_500),
+        adaptive.lookup(50_000),
+    ];
+    let expected = [0, 2, 4, 14, 15];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn lookup_empty_curve() {
+    let adaptive = AdaptiveBrightness::new(&[], 0);
+
+    assert_eq!(adaptive.lookup(100), MAX_LEVEL);
+}
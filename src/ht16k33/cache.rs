@@ -0,0 +1,311 @@
+/*
+    remember what was sent to the device to minimise the I²C traffic
+
+    - the setup commands (oscillator, blink rate, brightness) are only
+      sent if the configuration changed
+    - only the range from the first to the last changed byte of the
+      display RAM is written
+
+    The cache also tracks the power state. While the display is not
+    'Active' nothing is sent, the last frame is kept and sent (together
+    with the configuration) once the display is enabled again.
+
+    The display functions borrow the display immutably, the cache uses
+    interior mutability. Use force_refresh() on the display to recover
+    after the device was reset (e.g. a power glitch).
+
+    DisplayControl bundles the cache with the display mode and the
+    brightness level and is shared by all displays (segmented displays,
+    LED matrices and the bar graph). The displays expose it through the
+    LedDisplay trait, the device ID is passed in.
+*/
+
+use core::cell::Cell;
+
+use crate::ht16k33::hw::{self, BlinkRate, Dimming, DisplayRamAddress, Oscillator};
+use crate::ht16k33::{DeviceId, PowerState};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+struct DisplayCache {
+    address: Cell<Option<u8>>, // the device the cache refers to
+    configuration: Cell<Option<(BlinkRate, Dimming)>>,
+    display_data: Cell<Option<[u8; 16]>>, // what was sent to the device
+    frame: Cell<Option<[u8; 16]>>,        // the last frame shown by the user
+    power_state: Cell<PowerState>,
+}
+
+impl DisplayCache {
+    pub const fn new() -> Self {
+        DisplayCache {
+            address: Cell::new(None),
+            configuration: Cell::new(None),
+            display_data: Cell::new(None),
+            frame: Cell::new(None),
+            power_state: Cell::new(PowerState::Active),
+        }
+    }
+
+    /// forget everything, the next update sends the full configuration
+    /// and display data
+    pub fn invalidate(&self) {
+        self.configuration.set(None);
+        self.display_data.set(None);
+    }
+
+    pub fn get_power_state(&self) -> PowerState {
+        self.power_state.get()
+    }
+
+    /// change the power state
+    /// - Standby: the oscillator is turned off
+    /// - Idle: the oscillator is running but the display is turned off
+    /// - Active: the configuration and the last frame are restored
+    pub fn set_power_state<Ibd>(
+        &self,
+        ibd: &mut Ibd,
+        da: u8,
+        power_state: PowerState,
+        blink_rate: BlinkRate,
+        dimming: Dimming,
+    ) where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.select_device(da);
+        let previous = self.power_state.replace(power_state);
+        if previous == power_state && power_state != PowerState::Active {
+            return;
+        }
+
+        debug!("Power state of {0:#04X}: {1:?}", da, power_state);
+        self.apply_power_state(ibd, da, blink_rate, dimming);
+    }
+
+    /// enable the oscillator and apply the configuration
+    /// (nothing is sent if the configuration did not change or the
+    /// display is not active)
+    pub fn configure<Ibd>(&self, ibd: &mut Ibd, da: u8, blink_rate: BlinkRate, dimming: Dimming)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.select_device(da);
+        let configuration = (blink_rate, dimming);
+        if self.power_state.get() != PowerState::Active
+            || self.configuration.get() == Some(configuration)
+        {
+            return;
+        }
+
+        // order is important
+        // make sure the oscillator is enabled or this becomes a no-op
+        hw::set_oscillator_mode(ibd, da, Oscillator::On);
+        // configure display
+        hw::set_blink_rate(ibd, da, blink_rate);
+        hw::set_brightness_level(ibd, da, dimming);
+        self.configuration.set(Some(configuration));
+    }
+
+    /// send the changed part of the display data
+    /// (nothing is sent if the display data did not change, the frame is
+    /// kept until the display is active)
+    pub fn show<Ibd>(&self, ibd: &mut Ibd, da: u8, buffer: &[u8; 16])
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.select_device(da);
+        self.frame.set(Some(*buffer));
+        if self.power_state.get() != PowerState::Active {
+            return;
+        }
+
+        match self.display_data.get() {
+            None => hw::set_display_data(ibd, da, buffer),
+            Some(previous) => {
+                if let Some((first, last)) = find_dirty_range(&previous, buffer) {
+                    // the dirty range is always within the display RAM
+                    if let Ok(start) = DisplayRamAddress::try_from(first as u8) {
+                        let _ = hw::set_display_data_range(ibd, da, start, &buffer[first..=last]);
+                    }
+                }
+            }
+        }
+        self.display_data.set(Some(*buffer));
+    }
+
+    /// send the power state, configuration and the last frame
+    /// unconditionally
+    pub fn refresh<Ibd>(&self, ibd: &mut Ibd, da: u8, blink_rate: BlinkRate, dimming: Dimming)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.select_device(da);
+        self.invalidate();
+        self.apply_power_state(ibd, da, blink_rate, dimming);
+    }
+
+    fn apply_power_state<Ibd>(&self, ibd: &mut Ibd, da: u8, blink_rate: BlinkRate, dimming: Dimming)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        match self.power_state.get() {
+            PowerState::Standby => {
+                // the display RAM is retained
+                hw::set_oscillator_mode(ibd, da, Oscillator::Off);
+                self.configuration.set(None);
+            }
+            PowerState::Idle => {
+                // keep the oscillator running (e.g. for key scanning)
+                hw::set_oscillator_mode(ibd, da, Oscillator::On);
+                hw::set_blink_rate(ibd, da, BlinkRate::DisplayOff);
+                self.configuration.set(None);
+            }
+            PowerState::Active => {
+                self.configure(ibd, da, blink_rate, dimming);
+                if let Some(frame) = self.frame.get() {
+                    self.show(ibd, da, &frame);
+                }
+            }
+        }
+    }
+
+    // the device ID may be changed at any time
+    fn select_device(&self, da: u8) {
+        if self.address.get() != Some(da) {
+            self.invalidate();
+            self.address.set(Some(da));
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+// shared display state
+// ------------------------------------------------------------------------
+
+pub struct DisplayControl {
+    blink_rate: BlinkRate,
+    dimming: Dimming,
+    cache: DisplayCache, // what was sent to the device
+}
+
+impl DisplayControl {
+    pub const fn new(blink_rate: BlinkRate, dimming: Dimming) -> Self {
+        DisplayControl {
+            blink_rate,
+            dimming,
+            cache: DisplayCache::new(),
+        }
+    }
+
+    pub fn get_blink_rate(&self) -> BlinkRate {
+        self.blink_rate
+    }
+
+    /// update the blink rate and apply the configuration
+    pub fn set_blink_rate<Ibd>(&mut self, ibd: &mut Ibd, did: DeviceId, blink_rate: BlinkRate)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.blink_rate = blink_rate;
+        self.configure(ibd, did);
+    }
+
+    pub fn get_brightness_level(&self) -> u8 {
+        self.dimming.get_level()
+    }
+
+    /// update the brightness level and apply the configuration
+    /// - returns 'false' if the brightness level is not in range 0 ≤ x ≤ 15
+    pub fn set_brightness_level<Ibd>(
+        &mut self,
+        ibd: &mut Ibd,
+        did: DeviceId,
+        brightness_level: u8,
+    ) -> bool
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        match Dimming::try_from(brightness_level) {
+            Ok(dimming) => {
+                self.dimming = dimming;
+                self.configure(ibd, did);
+                true
+            }
+            Err(_) => {
+                error!("Brightness level must be in range 0 ≤ x ≤ 15");
+                false
+            }
+        }
+    }
+
+    pub fn get_power_state(&self) -> PowerState {
+        self.cache.get_power_state()
+    }
+
+    /// change the power state (see DisplayCache::set_power_state())
+    pub fn set_power_state<Ibd>(&self, ibd: &mut Ibd, did: DeviceId, power_state: PowerState)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = did.get_address();
+        self.cache
+            .set_power_state(ibd, da, power_state, self.blink_rate, self.dimming);
+    }
+
+    /// apply the configuration (only sent if it changed)
+    pub fn configure<Ibd>(&self, ibd: &mut Ibd, did: DeviceId)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = did.get_address();
+        self.cache.configure(ibd, da, self.blink_rate, self.dimming);
+    }
+
+    /// apply the configuration and send the changed part of the buffer
+    pub fn show<Ibd>(&self, ibd: &mut Ibd, did: DeviceId, buffer: &[u8; 16])
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.configure(ibd, did);
+
+        // send data (only the changed range)
+        let da = did.get_address();
+        self.cache.show(ibd, da, buffer);
+    }
+
+    /// send the power state, configuration and the last frame
+    /// unconditionally
+    pub fn refresh<Ibd>(&self, ibd: &mut Ibd, did: DeviceId)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = did.get_address();
+        self.cache.refresh(ibd, da, self.blink_rate, self.dimming);
+    }
+}
+
+/// the first and the last index where the buffers differ
+/// - returns 'None' if the buffers are identical
+fn find_dirty_range(previous: &[u8; 16], current: &[u8; 16]) -> Option<(usize, usize)> {
+    let first = (0..16).find(|idx| previous[*idx] != current[*idx])?;
+    let last = (0..16).rfind(|idx| previous[*idx] != current[*idx])?;
+
+    // implicit return
+    Some((first, last))
+}
+
+#[test]
+fn find_dirty_range_pass() {
+    let previous = [0u8; 16];
+    let mut current = [0u8; 16];
+    current[3] = 0x01;
+    current[9] = 0x01;
+
+    let computed = [
+        find_dirty_range(&previous, &previous),
+        find_dirty_range(&previous, &current),
+    ];
+    let expected = [None, Some((3, 9))];
+
+    assert_eq!(computed, expected);
+}
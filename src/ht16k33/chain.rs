@@ -0,0 +1,212 @@
+/*
+    combine several displays into a single logical display
+
+    The members are ordered from left to right. Each member is identified
+    by its own device ID (did). Any display can be chained, text and
+    numbers are only supported by chains of segmented displays.
+*/
+
+use crate::ht16k33::{
+    BlinkRate, ConfigurationError, Fit, FormattedNumber, LedDisplay, NumberFormat, SegmentedDisplay,
+};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// several displays side by side, used as one logical display
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut chain = DisplayChain::new([
+///     Segment14x4::new(convert_14, 0, BlinkRate::NoBlink, 8)?,
+///     Segment14x4::new(convert_14, 1, BlinkRate::NoBlink, 8)?,
+/// ])?;
+/// chain.show_string(&mut ibd, "FAN FAIL");
+/// chain.show_number(&mut ibd, -12.345);  // "  -12.345"
+/// ```
+pub struct DisplayChain<D, const N: usize> {
+    pub members: [D; N],
+    buffers: [[u8; 16]; N],
+}
+
+impl<D, const N: usize> DisplayChain<D, N>
+where
+    D: LedDisplay,
+{
+    /// create a new chain, the members are ordered from left to right
+    /// - each member must use a different device ID
+    pub fn new(members: [D; N]) -> Result<Self, ConfigurationError> {
+        for (idx, member) in members.iter().enumerate() {
+            let did = member.get_did();
+            if members[..idx].iter().any(|m| m.get_did() == did) {
+                return Err(ConfigurationError::DuplicateDeviceId(did.get_value()));
+            }
+        }
+
+        let chain = DisplayChain {
+            members,
+            buffers: [[0b0000_0000; 16]; N],
+        };
+
+        // implicit return
+        Ok(chain)
+    }
+
+    /// enter standby mode (all members)
+    pub fn disable<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        for member in self.members.iter() {
+            member.disable(ibd);
+        }
+    }
+
+    /// leave standby mode (all members)
+    pub fn enable<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        for member in self.members.iter() {
+            member.enable(ibd);
+        }
+    }
+
+    /// set the blink rate of all members
+    /// (deferred while the displays are disabled)
+    pub fn set_display_mode<Ibd>(&mut self, ibd: &mut Ibd, display_mode: BlinkRate)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        for member in self.members.iter_mut() {
+            member.set_display_mode(ibd, display_mode);
+        }
+    }
+
+    /// set the brightness level of all members
+    /// (deferred while the displays are disabled)
+    /// - brightness level is graduated from 0 (6%) to 15 (100%)
+    pub fn set_brightness_level<Ibd>(&mut self, ibd: &mut Ibd, brightness_level: u8) -> bool
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let mut is_ok = true;
+        for member in self.members.iter_mut() {
+            is_ok &= member.set_brightness_level(ibd, brightness_level);
+        }
+
+        // implicit return
+        is_ok
+    }
+
+    /// send the data buffers to all members
+    /// - members with a framebuffer (LED matrices, bar graphs) send their
+    ///   framebuffer
+    /// - segmented displays send what the chain rendered
+    pub fn flush<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        for (member, buffer) in self.members.iter().zip(self.buffers.iter()) {
+            let buffer = member.get_framebuffer().unwrap_or(buffer);
+            member.show_buffer(ibd, buffer);
+        }
+    }
+
+    /// send the configuration and the last data buffers again (all members)
+    /// (use this to recover after the devices were reset)
+    pub fn force_refresh<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        for member in self.members.iter() {
+            member.force_refresh(ibd);
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+// segmented displays
+// ------------------------------------------------------------------------
+
+impl<D, const N: usize> DisplayChain<D, N>
+where
+    D: SegmentedDisplay,
+{
+    /// the number of digits of all members combined
+    pub fn get_digit_count(&self) -> usize {
+        self.members.iter().map(|m| m.get_digit_count()).sum()
+    }
+
+    /// display the provided string across all members
+    /// (same rules as SegmentedDisplay::show_string())
+    pub fn show_string<Ibd>(&mut self, ibd: &mut Ibd, string: &str) -> Fit
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let fit = self.render(string.chars());
+        if let Fit::Truncated(count) = fit {
+            warn!("String '{string}' was truncated to {count} characters.");
+        }
+
+        self.flush(ibd);
+
+        // implicit return
+        fit
+    }
+
+    /// display the provided number, right-aligned across all members
+    /// - the number of decimals is reduced to make the number fit
+    /// - returns 'false' (and shows dashes) if the number does not fit
+    pub fn show_number<Ibd>(&mut self, ibd: &mut Ibd, number: f32) -> bool
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let format = NumberFormat::new(self.get_digit_count());
+        let formatted = format.format_float(number);
+        if formatted.is_overflow() {
+            warn!("Number {number} does not fit on {} digits.", format.digits);
+        }
+
+        // implicit return
+        self.show_formatted(ibd, &formatted)
+    }
+
+    /// display the output of NumberFormat across all members
+    /// - returns 'false' if the number did not fit
+    pub fn show_formatted<Ibd>(&mut self, ibd: &mut Ibd, number: &FormattedNumber) -> bool
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let fit = self.render(number.chars());
+
+        self.flush(ibd);
+
+        // implicit return
+        fit == Fit::Complete && !number.is_overflow()
+    }
+
+    // split the characters across the members and update the data buffers
+    fn render<I>(&mut self, chars: I) -> Fit
+    where
+        I: Iterator<Item = char> + Clone,
+    {
+        let length = chars.clone().count();
+        let mut offset = 0;
+        for (member, buffer) in self.members.iter().zip(self.buffers.iter_mut()) {
+            *buffer = [0b0000_0000; 16];
+            match member.render_chars(chars.clone().skip(offset), buffer) {
+                Fit::Complete => offset = length,
+                Fit::Truncated(count) => offset += count,
+            }
+        }
+
+        // implicit return
+        if offset == length {
+            Fit::Complete
+        } else {
+            Fit::Truncated(offset)
+        }
+    }
+}
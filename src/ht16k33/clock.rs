@@ -0,0 +1,329 @@
+/*
+    clock and countdown for the 7-segment backpack
+
+    The colon is a dedicated segment (buffer position 4) and does not use
+    a digit. To blink the colon independently of the rest of the display,
+    alternate between frames with and without colon (e.g. toggle
+    'is_colon_on' every 500ms).
+*/
+
+use crate::ht16k33::{GlyphTable, Segment7x4, SegmentedDisplay};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeFormat {
+    Hour12,       // " 1:05" (hours 1-12, no leading zero)
+    Hour24,       // " 9:05" (no leading zero)
+    Hour24Padded, // "09:05"
+}
+
+impl<G> Segment7x4<G>
+where
+    G: GlyphTable,
+{
+    /// display the provided time (e.g. "12:34")
+    /// (deferred while the display is disabled)
+    /// - hours must be in range 0 ≤ x ≤ 23, minutes in range 0 ≤ x ≤ 59
+    pub fn show_time<Ibd>(
+        &self,
+        ibd: &mut Ibd,
+        hours: u8,
+        minutes: u8,
+        time_format: TimeFormat,
+        is_colon_on: bool,
+    ) -> bool
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        match format_time(hours, minutes, time_format) {
+            Some(digits) => {
+                self.show_clock_digits(ibd, &digits, is_colon_on);
+                true
+            }
+            None => {
+                error!("Time {hours}:{minutes} is invalid.");
+                false
+            }
+        }
+    }
+
+    /// display the provided duration
+    /// (deferred while the display is disabled)
+    /// - "MM:SS" for durations below 1 hour
+    /// - "HHMM" for durations below 10 hours (e.g. "1H05", no colon)
+    /// - " HHH" for durations below 100 hours (e.g. " 12H", full hours)
+    /// - returns 'false' (and shows dashes) if the duration does not fit
+    ///
+    /// The hour formats use an 'H' instead of the colon, so 1 hour ("1H00")
+    /// can not be mistaken for 1 minute (" 1:00").
+    pub fn show_duration<Ibd>(&self, ibd: &mut Ibd, seconds: u32, is_colon_on: bool) -> bool
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        match format_duration(seconds) {
+            Some(digits) => {
+                // the colon is only used for "MM:SS"
+                self.show_clock_digits(ibd, &digits, is_colon_on && seconds < 3600);
+                true
+            }
+            None => {
+                warn!("Duration of {seconds}s does not fit on 4 digits.");
+                self.show_clock_digits(ibd, &['-'; 4], is_colon_on);
+                false
+            }
+        }
+    }
+
+    fn show_clock_digits<Ibd>(&self, ibd: &mut Ibd, digits: &[char; 4], is_colon_on: bool)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let chars = [digits[0], digits[1], ':', digits[2], digits[3]];
+        if is_colon_on {
+            self.show_chars(ibd, &chars);
+        } else {
+            self.show_chars(ibd, digits);
+        }
+    }
+}
+
+/// an MM:SS countdown, driven by the caller
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut countdown = Countdown::new(5 * 60);
+/// while !countdown.is_finished() {
+///     countdown.show(&sd, &mut ibd);  // " 5:00", " 4:59", ...
+///     ibd.sleep_ms(250);
+///     countdown.update(250);
+/// }
+/// ```
+pub struct Countdown {
+    duration_ms: u64,
+    remaining_ms: u64,
+}
+
+impl Countdown {
+    pub const fn new(seconds: u32) -> Self {
+        Countdown {
+            duration_ms: seconds as u64 * 1000,
+            remaining_ms: seconds as u64 * 1000,
+        }
+    }
+
+    /// start over with the initial duration
+    pub fn reset(&mut self) {
+        self.remaining_ms = self.duration_ms;
+    }
+
+    /// advance the countdown
+    /// - returns 'true' if the countdown finished during this update
+    pub fn update(&mut self, elapsed_ms: u64) -> bool {
+        let was_finished = self.is_finished();
+        self.remaining_ms = self.remaining_ms.saturating_sub(elapsed_ms);
+
+        // implicit return
+        !was_finished && self.is_finished()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining_ms == 0
+    }
+
+    /// the remaining time, rounded up
+    /// (shows "0:00" only once the countdown has finished)
+    pub fn get_remaining_seconds(&self) -> u32 {
+        self.remaining_ms.div_ceil(1000) as u32
+    }
+
+    /// the colon blinks once per second while the countdown is running
+    pub fn is_colon_on(&self) -> bool {
+        self.is_finished() || self.remaining_ms % 1000 >= 500
+    }
+
+    /// display the remaining time
+    pub fn show<G, Ibd>(&self, display: &Segment7x4<G>, ibd: &mut Ibd) -> bool
+    where
+        G: GlyphTable,
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        // implicit return
+        display.show_duration(ibd, self.get_remaining_seconds(), self.is_colon_on())
+    }
+}
+
+// ------------------------------------------------------------------------
+// helper functions
+// ------------------------------------------------------------------------
+
+fn convert_digit(value: u32) -> char {
+    // implicit return
+    (b'0' + (value % 10) as u8) as char
+}
+
+/// convert the time into 4 digits (without colon)
+/// - returns 'None' if the time is invalid
+fn format_time(hours: u8, minutes: u8, time_format: TimeFormat) -> Option<[char; 4]> {
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    let hours = match time_format {
+        TimeFormat::Hour12 => match hours % 12 {
+            0 => 12,
+            h => h,
+        },
+        TimeFormat::Hour24 | TimeFormat::Hour24Padded => hours,
+    } as u32;
+    let is_padded = time_format == TimeFormat::Hour24Padded;
+
+    let mut digits = [
+        convert_digit(hours / 10),
+        convert_digit(hours),
+        convert_digit(minutes as u32 / 10),
+        convert_digit(minutes as u32),
+    ];
+    if hours < 10 && !is_padded {
+        digits[0] = ' ';
+    }
+
+    // implicit return
+    Some(digits)
+}
+
+/// convert the duration into 4 digits (without colon)
+/// - "MM:SS" below 1 hour, "HHMM" below 10 hours, " HHH" below 100 hours
+/// - returns 'None' if the duration exceeds 99 hours
+fn format_duration(seconds: u32) -> Option<[char; 4]> {
+    let hours = seconds / 3600;
+    let digits = if hours == 0 {
+        let (minutes, seconds) = (seconds / 60, seconds % 60);
+        let tens = if minutes < 10 {
+            ' '
+        } else {
+            convert_digit(minutes / 10)
+        };
+        [
+            tens,
+            convert_digit(minutes),
+            convert_digit(seconds / 10),
+            convert_digit(seconds),
+        ]
+    } else if hours < 10 {
+        let minutes = seconds / 60 % 60;
+        [
+            convert_digit(hours),
+            'H',
+            convert_digit(minutes / 10),
+            convert_digit(minutes),
+        ]
+    } else if hours < 100 {
+        [' ', convert_digit(hours / 10), convert_digit(hours), 'H']
+    } else {
+        return None;
+    };
+
+    // implicit return
+    Some(digits)
+}
+
+#[test]
+fn format_time_pass() {
+    let computed = [
+        format_time(0, 5, TimeFormat::Hour12),
+        format_time(13, 45, TimeFormat::Hour12),
+        format_time(12, 0, TimeFormat::Hour12),
+        format_time(9, 5, TimeFormat::Hour24),
+        format_time(9, 5, TimeFormat::Hour24Padded),
+        format_time(23, 59, TimeFormat::Hour24),
+    ];
+    let expected = [
+        Some(['1', '2', '0', '5']),
+        Some([' ', '1', '4', '5']),
+        Some(['1', '2', '0', '0']),
+        Some([' ', '9', '0', '5']),
+        Some(['0', '9', '0', '5']),
+        Some(['2', '3', '5', '9']),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_time_fail() {
+    let computed = [
+        format_time(24, 0, TimeFormat::Hour24),
+        format_time(12, 60, TimeFormat::Hour12),
+    ];
+    let expected = [None, None];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_duration_pass() {
+    let computed = [
+        format_duration(0),
+        format_duration(65),
+        format_duration(3599),
+        format_duration(3600),
+        format_duration(35_999),
+        format_duration(36_000),
+        format_duration(359_999),
+        format_duration(360_000),
+    ];
+    let expected = [
+        Some([' ', '0', '0', '0']),
+        Some([' ', '1', '0', '5']),
+        Some(['5', '9', '5', '9']),
+        Some(['1', 'H', '0', '0']),
+        Some(['9', 'H', '5', '9']),
+        Some([' ', '1', '0', 'H']),
+        Some([' ', '9', '9', 'H']),
+        None,
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn countdown_update() {
+    let mut countdown = Countdown::new(2);
+
+    let computed = [
+        (
+            countdown.update(400),
+            countdown.get_remaining_seconds(),
+            countdown.is_colon_on(),
+        ),
+        (
+            countdown.update(200),
+            countdown.get_remaining_seconds(),
+            countdown.is_colon_on(),
+        ),
+        (
+            countdown.update(1400),
+            countdown.get_remaining_seconds(),
+            countdown.is_colon_on(),
+        ),
+        (
+            countdown.update(100),
+            countdown.get_remaining_seconds(),
+            countdown.is_colon_on(),
+        ),
+    ];
+    let expected = [
+        (false, 2, true),
+        (false, 2, false),
+        (true, 0, true),
+        (false, 0, true),
+    ];
+
+    assert_eq!(computed, expected);
+
+    countdown.reset();
+    assert_eq!(countdown.get_remaining_seconds(), 2);
+}
@@ -2,52 +2,118 @@
 // generic trait for segmented display
 // ========================================================================
 
-#[derive(Copy, Clone)]
-pub enum DisplayMode {
-    Off = 0b0000,         // display disabled
-    On = 0b0001,          // display enabled (no blinking)
-    BlinkFast = 0b0011,   // blinking, 2Hz
-    BlinkMedium = 0b0101, // blinking, 1Hz
-    BlinkSlow = 0b0111,   // blinking, 0.5Hz
+use crate::ht16k33::did::Package;
+use crate::ht16k33::{Alignment, Fit, FormattedNumber, LedDisplay, NumberFormat, Overflow};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the provided configuration is not supported by the hardware
+#[derive(Debug, PartialEq)]
+pub enum ConfigurationError {
+    /// the device ID must be in range 0 ≤ x ≤ 7
+    InvalidDeviceId(u8),
+    /// the I²C address must be in range 0x70 ≤ x ≤ 0x77
+    InvalidAddress(u8),
+    /// the package does not have enough address pins for this device ID
+    UnsupportedDeviceId(u8, Package),
+    /// the brightness level must be in range 0 ≤ x ≤ 15
+    InvalidBrightnessLevel(u8),
+    /// the wiring refers to a (row, com) bit which does not exist
+    /// (rows must be in range 0 ≤ x ≤ 15, common lines in range 0 ≤ x ≤ 7)
+    InvalidWiring(u8, u8),
+    /// the display RAM address must be in range 0x00 ≤ x ≤ 0x0F, the key
+    /// RAM address in range 0x00 ≤ x ≤ 0x05
+    InvalidRamAddress(u8),
+    /// the display data does not fit into the display RAM
+    /// (at most 16 bytes, starting at the display RAM address)
+    InvalidDataLength(usize),
+    /// the number of debounce scans must be in range 1 ≤ x ≤ 255
+    InvalidDebounceScans(u8),
+    /// the device ID is used by more than one member of a chain
+    DuplicateDeviceId(u8),
 }
 
-pub trait SegmentedDisplay {
-    /// enter standby mode
-    /// - display will be turned off
-    /// - configuration is reset
-    /// - device must be woken up before doing anything
-    ///
-    /// This is the most energy-efficient mode. Use DisplayMode
-    /// 'Off' to disable the output but keep it running.
-    fn disable<Ibd>(&self, ibd: &mut Ibd)
+pub trait SegmentedDisplay: LedDisplay {
+    /// display the provided number, left-aligned
+    /// (deferred while the display is disabled)
+    /// - uses as many decimals as possible (up to 3)
+    /// - returns 'false' (and shows "-hi-" or "-lo-") if the number does not fit
+    fn show_number<Ibd>(&self, ibd: &mut Ibd, number: f32) -> bool
     where
-        Ibd: crate::traits::I2cBusDevice;
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let format = NumberFormat {
+            alignment: Alignment::Left,
+            overflow: Overflow::Text("-hi-", "-lo-"),
+            ..NumberFormat::new(self.get_digit_count())
+        };
 
-    fn set_display_mode<Ibd>(&mut self, ibd: &mut Ibd, display_mode: DisplayMode)
-    where
-        Ibd: crate::traits::I2cBusDevice;
+        // implicit return
+        self.show_formatted(ibd, &format.format_float(number))
+    }
 
-    /// set the display's brightness level
-    /// (implicitly enables the display)
-    /// - brightness level is graduated from 0 (6%) to 15 (100%)
-    /// - use the disable() function to turn off the display entirely
-    fn set_brightness_level<Ibd>(&mut self, ibd: &mut Ibd, brightness_level: u8) -> bool
+    /// display the output of NumberFormat
+    /// (deferred while the display is disabled)
+    /// - returns 'false' if the number did not fit
+    fn show_formatted<Ibd>(&self, ibd: &mut Ibd, number: &FormattedNumber) -> bool
     where
-        Ibd: crate::traits::I2cBusDevice;
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let mut buffer = [0b0000_0000; 16];
+        let fit = self.render_chars(number.chars(), &mut buffer);
 
-    /// display the provided data buffer
-    /// (implicitly enables the display)
-    /// - this is the most flexible way to address the display but the caller
-    ///   needs to know which bit controls which matrix dot / segment
-    fn show_buffer<Ibd>(&self, ibd: &mut Ibd, buffer: &[u8; 16])
+        self.show_buffer(ibd, &buffer);
+
+        // implicit return
+        fit == Fit::Complete && !number.is_overflow()
+    }
+
+    /// display the provided string
+    /// (deferred while the display is disabled)
+    /// - a dot is merged into the preceding digit
+    /// - colon, apostrophe and degree use the indicator position (if any)
+    /// - returns 'Truncated' if the string does not fit
+    fn show_string<Ibd>(&self, ibd: &mut Ibd, string: &str) -> Fit
     where
-        Ibd: crate::traits::I2cBusDevice;
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let mut buffer = [0b0000_0000; 16];
+        let fit = self.render_chars(string.chars(), &mut buffer);
+        if let Fit::Truncated(count) = fit {
+            warn!("String '{string}' was truncated to {count} characters.");
+        }
 
-    fn show_number<Ibd>(&self, ibd: &mut Ibd, number: f32) -> bool
+        self.show_buffer(ibd, &buffer);
+
+        // implicit return
+        fit
+    }
+
+    /// display the provided characters
+    /// (same rules as show_string())
+    fn show_chars<Ibd>(&self, ibd: &mut Ibd, chars: &[char]) -> Fit
     where
-        Ibd: crate::traits::I2cBusDevice;
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let mut buffer = [0b0000_0000; 16];
+        let fit = self.render_chars(chars.iter().copied(), &mut buffer);
+
+        self.show_buffer(ibd, &buffer);
+
+        // implicit return
+        fit
+    }
+
+    /// the number of digits (excluding the indicators)
+    fn get_digit_count(&self) -> usize;
+
+    /// the character is shown by an indicator (it does not use a digit)
+    fn is_indicator(&self, c: char) -> bool;
 
-    fn show_string<Ibd>(&self, ibd: &mut Ibd, string: &str) -> bool
+    /// convert the provided characters into a data buffer
+    /// (same rules as show_string(), nothing is sent to the device)
+    fn render_chars<I>(&self, chars: I, buffer: &mut [u8; 16]) -> Fit
     where
-        Ibd: crate::traits::I2cBusDevice;
+        I: Iterator<Item = char>;
 }
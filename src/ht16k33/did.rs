@@ -1,8 +1,12 @@
 /*
     map device ID to I²C address and vice versa
+
+    The number of devices per I²C bus depends on the package type. The
+    device ID is validated when a DeviceId is created, there is no need
+    to validate it again when the address is computed.
 */
 
-use core::panic;
+use crate::ht16k33::ConfigurationError;
 
 #[allow(unused_imports)]
 use log::{debug, error, info, warn};
@@ -10,57 +14,175 @@ use log::{debug, error, info, warn};
 // I²C base address
 static ADR: u8 = 0x70;
 
+// highest device ID (28SOP-A)
+const MAX_DID: u8 = 7;
+
+/// the package type determines the number of address pins
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Package {
+    Sop20, // 20SOP-A: no address pins, 1 device
+    Sop24, // 24SOP-A: A0 & A1, 4 devices
+    Sop28, // 28SOP-A: A0, A1 & A2, 8 devices
+}
+
+impl Package {
+    /// the maximum number of devices on a single I²C bus
+    pub const fn get_device_count(&self) -> u8 {
+        match self {
+            Package::Sop20 => 1,
+            Package::Sop24 => 4,
+            Package::Sop28 => 8,
+        }
+    }
+}
+
+/// a validated device ID (range: 0 ≤ x ≤ 7)
+///
+/// usage:
+/// ```TEXT
+///
+/// // checked at compile time
+/// let did = DeviceId::new::<3>();
+/// // checked at runtime
+/// let did = DeviceId::try_from(value)?;
+/// let did = DeviceId::for_package(value, Package::Sop24)?;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceId(u8);
+
+impl DeviceId {
+    /// create a device ID which is known at compile time
+    /// - an invalid value fails to compile
+    /// - use DeviceId::try_from() for values known at runtime only
+    pub const fn new<const DID: u8>() -> Self {
+        const { assert!(DID <= MAX_DID, "Device ID must be in range 0 ≤ x ≤ 7!") };
+
+        // implicit return
+        DeviceId(DID)
+    }
+
+    /// create a device ID which is supported by the provided package
+    pub const fn for_package(did: u8, package: Package) -> Result<Self, ConfigurationError> {
+        if did < package.get_device_count() {
+            Ok(DeviceId(did))
+        } else {
+            Err(ConfigurationError::UnsupportedDeviceId(did, package))
+        }
+    }
+
+    /// create a device ID from an I²C address (0x70 ≤ x ≤ 0x77)
+    pub const fn from_address(address: u8) -> Result<Self, ConfigurationError> {
+        if address >= ADR && address <= ADR + MAX_DID {
+            Ok(DeviceId(address - ADR))
+        } else {
+            Err(ConfigurationError::InvalidAddress(address))
+        }
+    }
+
+    pub const fn get_value(&self) -> u8 {
+        self.0
+    }
+
+    /// the device's I²C address
+    pub const fn get_address(&self) -> u8 {
+        ADR + self.0
+    }
+
+    const fn validate(did: u8) -> Result<Self, ConfigurationError> {
+        if did <= MAX_DID {
+            Ok(DeviceId(did))
+        } else {
+            Err(ConfigurationError::InvalidDeviceId(did))
+        }
+    }
+}
+
+impl TryFrom<u8> for DeviceId {
+    type Error = ConfigurationError;
+
+    fn try_from(did: u8) -> Result<Self, Self::Error> {
+        DeviceId::validate(did)
+    }
+}
+
 /// convert device ID to I²C address
 ///
 /// this is a convenience function so the user doesn't need to know the
 /// device's base address
-pub fn convert_did_to_address(did: u8) -> u8 {
-    const MAX: u8 = 7;
-    if did > MAX {
-        panic!("Device ID must be in range 0 ≤ x ≤ {MAX}!");
-    }
-
-    ADR + did
+pub fn convert_did_to_address(did: u8) -> Result<u8, ConfigurationError> {
+    // implicit return
+    DeviceId::try_from(did).map(|did| did.get_address())
 }
 
 #[test]
 fn convert_did_to_address_pass() {
     let computed = convert_did_to_address(0);
-    let expected = 0x70;
+    let expected = Ok(0x70);
 
     assert_eq!(computed, expected);
 }
 
 #[test]
-#[should_panic(expected = "Device ID must be in range 0 ≤ x ≤ 7!")]
 fn convert_did_to_address_fail() {
-    let _ = convert_did_to_address(8);
+    let computed = convert_did_to_address(8);
+    let expected = Err(ConfigurationError::InvalidDeviceId(8));
+
+    assert_eq!(computed, expected);
 }
 
 /// convert I²C address to device ID
 ///
 /// this is a convenience function so the user doesn't need to know the
 /// device's base address
-pub fn convert_address_to_did(address: u8) -> u8 {
-    const MIN: u8 = ADR;
-    const MAX: u8 = ADR + 7;
-    if !(MIN..=MAX).contains(&address) {
-        panic!("I²C address ID must be in range {MIN:#04x} ≤ x ≤ {MAX:#04x}!");
-    }
-
-    address - ADR
+pub fn convert_address_to_did(address: u8) -> Result<u8, ConfigurationError> {
+    // implicit return
+    DeviceId::from_address(address).map(|did| did.get_value())
 }
 
 #[test]
 fn convert_address_to_did_pass() {
     let computed = convert_address_to_did(0x70);
-    let expected = 0;
+    let expected = Ok(0);
 
     assert_eq!(computed, expected);
 }
 
 #[test]
-#[should_panic(expected = "I²C address ID must be in range 0x70 ≤ x ≤ 0x77!")]
 fn convert_address_to_did_fail() {
-    let _ = convert_address_to_did(0x78);
+    let computed = [convert_address_to_did(0x6F), convert_address_to_did(0x78)];
+    let expected = [
+        Err(ConfigurationError::InvalidAddress(0x6F)),
+        Err(ConfigurationError::InvalidAddress(0x78)),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn device_id_const() {
+    const DID: DeviceId = DeviceId::new::<7>();
+
+    assert_eq!(DID.get_address(), 0x77);
+}
+
+#[test]
+fn device_id_for_package() {
+    let computed = [
+        DeviceId::for_package(0, Package::Sop20),
+        DeviceId::for_package(1, Package::Sop20),
+        DeviceId::for_package(3, Package::Sop24),
+        DeviceId::for_package(4, Package::Sop24),
+        DeviceId::for_package(7, Package::Sop28),
+        DeviceId::for_package(8, Package::Sop28),
+    ];
+    let expected = [
+        Ok(DeviceId(0)),
+        Err(ConfigurationError::UnsupportedDeviceId(1, Package::Sop20)),
+        Ok(DeviceId(3)),
+        Err(ConfigurationError::UnsupportedDeviceId(4, Package::Sop24)),
+        Ok(DeviceId(7)),
+        Err(ConfigurationError::UnsupportedDeviceId(8, Package::Sop28)),
+    ];
+
+    assert_eq!(computed, expected);
 }
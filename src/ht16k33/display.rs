@@ -0,0 +1,143 @@
+/*
+    functions shared by all HT16K33 displays
+
+    Segmented displays, LED matrices and the bar graph only differ in the
+    way they fill the display RAM. The configuration (display mode and
+    brightness level), the power management and the communication with
+    the device are provided by the LedDisplay trait. A display only grants
+    access to its device ID, its DisplayControl and its framebuffer (if
+    any).
+*/
+
+use crate::ht16k33::{BlinkRate, DeviceId, DisplayControl, PowerState};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// configuration and power control shared by all HT16K33 displays
+/// (segmented displays, LED matrices and bar graphs)
+pub trait LedDisplay {
+    /// the device which is controlled
+    fn get_did(&self) -> DeviceId;
+
+    /// the display mode, brightness level and what was sent to the device
+    /// (used by the provided functions)
+    #[doc(hidden)]
+    fn control(&self) -> &DisplayControl;
+
+    #[doc(hidden)]
+    fn control_mut(&mut self) -> &mut DisplayControl;
+
+    /// the framebuffer which is sent by flush()
+    /// ('None' if the display has no framebuffer, e.g. segmented displays
+    /// send what they render right away)
+    fn get_framebuffer(&self) -> Option<&[u8; 16]> {
+        None
+    }
+
+    fn get_display_mode(&self) -> BlinkRate {
+        self.control().get_blink_rate()
+    }
+
+    /// set the display's blink rate
+    /// (deferred while the display is disabled)
+    fn set_display_mode<Ibd>(&mut self, ibd: &mut Ibd, display_mode: BlinkRate)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let did = self.get_did();
+        self.control_mut().set_blink_rate(ibd, did, display_mode);
+    }
+
+    fn get_brightness_level(&self) -> u8 {
+        self.control().get_brightness_level()
+    }
+
+    /// set the display's brightness level
+    /// (deferred while the display is disabled)
+    /// - brightness level is graduated from 0 (6%) to 15 (100%)
+    /// - use the disable() function to turn off the display entirely
+    fn set_brightness_level<Ibd>(&mut self, ibd: &mut Ibd, brightness_level: u8) -> bool
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let did = self.get_did();
+
+        // implicit return
+        self.control_mut()
+            .set_brightness_level(ibd, did, brightness_level)
+    }
+
+    /// display the provided data buffer
+    /// (deferred while the display is disabled)
+    /// - this is the most flexible way to address the display but the caller
+    ///   needs to know which bit controls which matrix dot / segment
+    /// - only the changed part of the data buffer is sent
+    fn show_buffer<Ibd>(&self, ibd: &mut Ibd, buffer: &[u8; 16])
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.control().show(ibd, self.get_did(), buffer);
+    }
+
+    /// send the framebuffer to the display
+    /// (deferred while the display is disabled)
+    /// - only the changed part of the framebuffer is sent
+    /// - does nothing if the display has no framebuffer
+    fn flush<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        if let Some(buffer) = self.get_framebuffer() {
+            self.show_buffer(ibd, buffer);
+        }
+    }
+
+    /// send the configuration and the last data buffer again
+    /// - the display remembers what was sent to the device and skips
+    ///   redundant updates
+    /// - use this to recover after the device was reset (e.g. power loss)
+    fn force_refresh<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.control().refresh(ibd, self.get_did());
+        self.flush(ibd);
+    }
+
+    fn get_power_state(&self) -> PowerState {
+        self.control().get_power_state()
+    }
+
+    /// change the power state (see PowerState)
+    fn set_power_state<Ibd>(&self, ibd: &mut Ibd, power_state: PowerState)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.control()
+            .set_power_state(ibd, self.get_did(), power_state);
+    }
+
+    /// enter standby mode
+    /// - display will be turned off
+    /// - the show functions only remember the frame until the display is
+    ///   enabled again
+    ///
+    /// This is the most energy-efficient mode. Use BlinkRate
+    /// 'DisplayOff' to disable the output but keep it running.
+    fn disable<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.set_power_state(ibd, PowerState::Standby);
+    }
+
+    /// leave standby (or idle) mode
+    /// - brightness level, display mode and the last frame are restored
+    fn enable<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.set_power_state(ibd, PowerState::Active);
+    }
+}
@@ -0,0 +1,377 @@
+/*
+    brightness and animation effects for segmented displays
+
+    The effects are driven by the caller, e.g. by calling tick() from a
+    timer or the main loop. Durations are expressed in ticks, there is
+    no dependency on a clock. The brightness is changed using the
+    display's set_brightness_level(), frames are sent using show_buffer().
+
+    The HT16K33 can not turn off the LEDs by dimming (level 0 is 1/16 duty
+    cycle). A cross-fade therefore dims the first frame to level 0, swaps
+    the frames and brightens the second frame again.
+*/
+
+use crate::ht16k33::hw::Dimming;
+use crate::ht16k33::SegmentedDisplay;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the maximum brightness level of the HT16K33
+const MAX_LEVEL: u8 = Dimming::Duty16.get_level();
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Effect {
+    /// change the brightness level gradually
+    Fade { from: u8, to: u8, ticks: u32 },
+    /// dim out the first frame and brighten the second frame
+    /// (ends at the provided brightness level)
+    CrossFade {
+        from: [u8; 16],
+        to: [u8; 16],
+        brightness_level: u8,
+        ticks: u32,
+    },
+    /// pulse between two brightness levels (runs until stopped)
+    Breathe { min: u8, max: u8, period: u32 },
+    /// show and hide the frame 'count' times (ends with the frame shown)
+    Flash {
+        frame: [u8; 16],
+        count: u32,
+        ticks_on: u32,
+        ticks_off: u32,
+    },
+}
+
+/// what needs to be sent to the display for a given tick
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EffectStep {
+    pub brightness_level: Option<u8>,
+    pub buffer: Option<[u8; 16]>,
+}
+
+/// play an effect on a segmented display
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut animation = Animation::new(Effect::Fade { from: 0, to: 15, ticks: 20 });
+/// // called every 50ms, the display fades in within 1 second
+/// while animation.tick(&mut sd, &mut ibd) {
+///     ibd.sleep_ms(50);
+/// }
+/// ```
+pub struct Animation {
+    effect: Effect,
+    ticks: u32, // number of elapsed ticks
+}
+
+impl Animation {
+    pub fn new(effect: Effect) -> Self {
+        Animation { effect, ticks: 0 }
+    }
+
+    /// restart the effect
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+    }
+
+    /// the total duration in ticks ('None' if the effect runs until stopped)
+    pub fn get_duration(&self) -> Option<u32> {
+        match self.effect {
+            Effect::Fade { ticks, .. } => Some(ticks),
+            Effect::CrossFade { ticks, .. } => Some(ticks),
+            Effect::Breathe { .. } => None,
+            Effect::Flash {
+                count,
+                ticks_on,
+                ticks_off,
+                ..
+            } => Some(count.saturating_mul(ticks_on.saturating_add(ticks_off))),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match self.get_duration() {
+            Some(duration) => self.ticks > duration,
+            None => false,
+        }
+    }
+
+    /// apply the current step to the display and advance by one tick
+    /// - returns 'false' once the effect has finished
+    pub fn tick<D, Ibd>(&mut self, display: &mut D, ibd: &mut Ibd) -> bool
+    where
+        D: SegmentedDisplay,
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        if self.is_finished() {
+            return false;
+        }
+
+        let step = self.get_step(self.ticks);
+        if let Some(brightness_level) = step.brightness_level {
+            display.set_brightness_level(ibd, brightness_level);
+        }
+        if let Some(buffer) = step.buffer {
+            display.show_buffer(ibd, &buffer);
+        }
+        self.advance();
+
+        // implicit return
+        !self.is_finished()
+    }
+
+    // move on to the next tick without overflowing
+    fn advance(&mut self) {
+        self.ticks = match self.effect {
+            // the wave repeats, start over after each period
+            // (ticks < period, the addition can not overflow)
+            Effect::Breathe { period, .. } => (self.ticks + 1) % period.max(2),
+            _ => self.ticks.saturating_add(1),
+        };
+    }
+
+    /// compute what needs to be displayed at the provided tick
+    pub fn get_step(&self, tick: u32) -> EffectStep {
+        match self.effect {
+            Effect::Fade { from, to, ticks } => EffectStep {
+                brightness_level: Some(interpolate(from, to, tick, ticks)),
+                buffer: None,
+            },
+            Effect::CrossFade {
+                from,
+                to,
+                brightness_level,
+                ticks,
+            } => {
+                // the first half dims out, the second half brightens
+                let half = ticks / 2;
+                if tick < half {
+                    EffectStep {
+                        brightness_level: Some(interpolate(brightness_level, 0, tick, half)),
+                        buffer: Some(from),
+                    }
+                } else {
+                    EffectStep {
+                        brightness_level: Some(interpolate(
+                            0,
+                            brightness_level,
+                            tick - half,
+                            ticks - half,
+                        )),
+                        buffer: Some(to),
+                    }
+                }
+            }
+            Effect::Breathe { min, max, period } => {
+                // triangle wave, starts at 'min'
+                let period = period.max(2);
+                let half = period / 2;
+                let phase = tick % period;
+                let level = if phase < half {
+                    interpolate(min, max, phase, half)
+                } else {
+                    interpolate(max, min, phase - half, period - half)
+                };
+                EffectStep {
+                    brightness_level: Some(level),
+                    buffer: None,
+                }
+            }
+            Effect::Flash {
+                frame,
+                count,
+                ticks_on,
+                ticks_off,
+            } => {
+                let period = ticks_on.saturating_add(ticks_off);
+                let is_on =
+                    period == 0 || tick >= count.saturating_mul(period) || tick % period < ticks_on;
+                EffectStep {
+                    brightness_level: None,
+                    buffer: Some(if is_on { frame } else { [0x00; 16] }),
+                }
+            }
+        }
+    }
+}
+
+/// linear interpolation between two brightness levels (rounded)
+/// - returns 'to' once 'tick' reaches 'ticks'
+fn interpolate(from: u8, to: u8, tick: u32, ticks: u32) -> u8 {
+    let from = from.min(MAX_LEVEL) as i64;
+    let to = to.min(MAX_LEVEL) as i64;
+    if tick >= ticks {
+        return to as u8;
+    }
+
+    let delta = (to - from) * tick as i64;
+    let ticks = ticks as i64;
+    // round half away from zero
+    let offset = if delta >= 0 {
+        (delta + ticks / 2) / ticks
+    } else {
+        (delta - ticks / 2) / ticks
+    };
+
+    // implicit return
+    (from + offset) as u8
+}
+
+#[cfg(test)]
+fn collect_levels(animation: &Animation, ticks: u32) -> [u8; 8] {
+    let mut result = [0u8; 8];
+    for (tick, level) in result.iter_mut().enumerate().take(ticks as usize) {
+        *level = animation.get_step(tick as u32).brightness_level.unwrap();
+    }
+
+    result
+}
+
+#[test]
+fn fade_in() {
+    let animation = Animation::new(Effect::Fade {
+        from: 0,
+        to: 15,
+        ticks: 4,
+    });
+
+    let computed = collect_levels(&animation, 6);
+    let expected = [0, 4, 8, 11, 15, 15, 0, 0];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn fade_out() {
+    let animation = Animation::new(Effect::Fade {
+        from: 15,
+        to: 0,
+        ticks: 4,
+    });
+
+    let computed = collect_levels(&animation, 5);
+    let expected = [15, 11, 7, 4, 0, 0, 0, 0];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn breathe() {
+    let animation = Animation::new(Effect::Breathe {
+        min: 2,
+        max: 10,
+        period: 4,
+    });
+
+    let computed = collect_levels(&animation, 8);
+    let expected = [2, 6, 10, 6, 2, 6, 10, 6];
+
+    assert_eq!(computed, expected);
+    assert_eq!(animation.get_duration(), None);
+}
+
+#[test]
+fn cross_fade() {
+    let animation = Animation::new(Effect::CrossFade {
+        from: [0x01; 16],
+        to: [0x02; 16],
+        brightness_level: 8,
+        ticks: 4,
+    });
+
+    let computed = [
+        animation.get_step(0),
+        animation.get_step(1),
+        animation.get_step(2),
+        animation.get_step(4),
+    ];
+    let expected = [
+        EffectStep {
+            brightness_level: Some(8),
+            buffer: Some([0x01; 16]),
+        },
+        EffectStep {
+            brightness_level: Some(4),
+            buffer: Some([0x01; 16]),
+        },
+        EffectStep {
+            brightness_level: Some(0),
+            buffer: Some([0x02; 16]),
+        },
+        EffectStep {
+            brightness_level: Some(8),
+            buffer: Some([0x02; 16]),
+        },
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn flash() {
+    let animation = Animation::new(Effect::Flash {
+        frame: [0xFF; 16],
+        count: 2,
+        ticks_on: 1,
+        ticks_off: 2,
+    });
+
+    let computed = [0, 1, 2, 3, 4, 5, 6].map(|tick| animation.get_step(tick).buffer.unwrap()[0]);
+    let expected = [0xFF, 0x00, 0x00, 0xFF, 0x00, 0x00, 0xFF];
+
+    assert_eq!(computed, expected);
+    assert_eq!(animation.get_duration(), Some(6));
+}
+
+#[test]
+fn flash_duration_overflow() {
+    let animation = Animation::new(Effect::Flash {
+        frame: [0xFF; 16],
+        count: u32::MAX,
+        ticks_on: u32::MAX,
+        ticks_off: 1,
+    });
+
+    let computed = animation.get_duration();
+    let expected = Some(u32::MAX);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn flash_step_overflow() {
+    let animation = Animation::new(Effect::Flash {
+        frame: [0xFF; 16],
+        count: 2,
+        ticks_on: u32::MAX,
+        ticks_off: u32::MAX,
+    });
+
+    let computed = animation.get_step(u32::MAX);
+    let expected = EffectStep {
+        brightness_level: None,
+        buffer: Some([0xFF; 16]),
+    };
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn breathe_tick_overflow() {
+    let mut animation = Animation::new(Effect::Breathe {
+        min: 0,
+        max: 15,
+        period: u32::MAX,
+    });
+    animation.ticks = u32::MAX - 2;
+    animation.advance();
+    let first = animation.ticks;
+    animation.advance();
+
+    let computed = [first, animation.ticks];
+    let expected = [u32::MAX - 1, 0];
+
+    assert_eq!(computed, expected);
+}
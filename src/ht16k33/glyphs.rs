@@ -0,0 +1,370 @@
+/*
+    glyph tables for 7-, 14- and 16-segment displays
+
+    A glyph table maps a character to its segment pattern (2-byte tuple).
+    Characters which can not be displayed are replaced by the table's
+    "unknown" glyph (blank by default).
+
+    - Glyphs7, Glyphs14 and Glyphs16 are the built-in tables
+    - LayeredGlyphs puts user-defined glyphs on top of another table,
+      layers can be nested and defined in const/static data
+    - any function or closure 'Fn(char) -> (u8, u8)' is a glyph table,
+      e.g. convert_7 or convert_14
+
+    Characters with diacritics (e.g. 'Ä', 'Ö', 'Ü') are approximated by
+    their base letter.
+*/
+
+use crate::ht16k33::converter::{lookup_14, lookup_7};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the segment pattern of a blank digit
+const BLANK: (u8, u8) = (0b0000_0000, 0b0000_0000);
+
+pub trait GlyphTable {
+    /// the segment pattern of the provided character
+    /// - returns 'None' if the character can not be displayed
+    fn get_glyph(&self, c: char) -> Option<(u8, u8)>;
+
+    /// the segment pattern shown for characters which can not be displayed
+    fn get_unknown_glyph(&self) -> (u8, u8) {
+        BLANK
+    }
+
+    /// the segment pattern of the provided character, falls back to the
+    /// unknown glyph
+    fn convert(&self, c: char) -> (u8, u8) {
+        // implicit return
+        self.get_glyph(c)
+            .unwrap_or_else(|| self.get_unknown_glyph())
+    }
+}
+
+/// converter functions (e.g. convert_7) and closures are glyph tables
+/// - every character is known, the function decides what to display
+impl<F> GlyphTable for F
+where
+    F: Fn(char) -> (u8, u8),
+{
+    fn get_glyph(&self, c: char) -> Option<(u8, u8)> {
+        Some(self(c))
+    }
+}
+
+// ========================================================================
+// built-in tables
+// ========================================================================
+
+/// the built-in 7-segment glyphs
+/// - upper case letters, digits and symbols (see convert_7)
+/// - lower case approximations (e.g. 'c', 'h', 'u')
+/// - symbols: ' ', '-', '_', '=', '°', 'µ' and the arrows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glyphs7 {
+    pub unknown: (u8, u8), // shown for characters which can not be displayed
+}
+
+impl Glyphs7 {
+    pub const fn new() -> Self {
+        Glyphs7 { unknown: BLANK }
+    }
+}
+
+impl Default for Glyphs7 {
+    fn default() -> Self {
+        Glyphs7::new()
+    }
+}
+
+impl GlyphTable for Glyphs7 {
+    fn get_glyph(&self, c: char) -> Option<(u8, u8)> {
+        let glyph = match c {
+            ' ' => BLANK,
+            '-' => (0b0100_0000, 0b0000_0000),
+            '_' => (0b0000_1000, 0b0000_0000),
+            '=' => (0b0100_1000, 0b0000_0000),
+            '°' => (0b0110_0011, 0b0000_0000),
+            'µ' => (0b0011_1100, 0b0000_0000),
+            // arrows use the segments on the side they point to
+            '←' => (0b0011_0000, 0b0000_0000),
+            '↑' => (0b0010_0011, 0b0000_0000),
+            '→' => (0b0000_0110, 0b0000_0000),
+            '↓' => (0b0001_1100, 0b0000_0000),
+            // lower case letters which differ from upper case
+            'a' => (0b0101_1111, 0b0000_0000),
+            'c' => (0b0101_1000, 0b0000_0000),
+            'e' => (0b0111_1011, 0b0000_0000),
+            'g' => (0b0110_1111, 0b0000_0000),
+            'h' => (0b0111_0100, 0b0000_0000),
+            'i' => (0b0001_0000, 0b0000_0000),
+            'u' => (0b0001_1100, 0b0000_0000),
+            // the remaining lower case letters look like upper case
+            'b' | 'd' | 'f' | 'j' | 'l' | 'n' | 'o' | 'p' | 'q' | 'r' | 's' | 't' | 'v' | 'y' => {
+                lookup_7(c.to_ascii_uppercase())?
+            }
+            _ => match lookup_7(c) {
+                Some(glyph) => glyph,
+                None => self.get_glyph(convert_to_base_letter(c)?)?,
+            },
+        };
+
+        // implicit return
+        Some(glyph)
+    }
+
+    fn get_unknown_glyph(&self) -> (u8, u8) {
+        self.unknown
+    }
+}
+
+/// the built-in 14-segment glyphs
+/// - printable ASCII characters (see convert_14)
+/// - symbols: ' ', '°', 'µ' and the arrows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glyphs14 {
+    pub unknown: (u8, u8), // shown for characters which can not be displayed
+}
+
+impl Glyphs14 {
+    pub const fn new() -> Self {
+        Glyphs14 { unknown: BLANK }
+    }
+}
+
+impl Default for Glyphs14 {
+    fn default() -> Self {
+        Glyphs14::new()
+    }
+}
+
+impl GlyphTable for Glyphs14 {
+    fn get_glyph(&self, c: char) -> Option<(u8, u8)> {
+        let glyph = match c {
+            ' ' => BLANK,
+            '°' => (0b0110_0001, 0b0000_0010),
+            'µ' => (0b0011_1100, 0b0000_0000),
+            // the diagonals form the arrow head
+            '←' => (0b1100_0000, 0b0010_0100),
+            '↑' => (0b0000_0000, 0b0011_1010),
+            '→' => (0b1100_0000, 0b0000_1001),
+            '↓' => (0b0000_0000, 0b0001_0111),
+            _ => match lookup_14(c) {
+                Some(glyph) => glyph,
+                None => self.get_glyph(convert_to_base_letter(c)?)?,
+            },
+        };
+
+        // implicit return
+        Some(glyph)
+    }
+
+    fn get_unknown_glyph(&self) -> (u8, u8) {
+        self.unknown
+    }
+}
+
+/// the built-in 16-segment glyphs (derived from the 14-segment glyphs)
+/// - segments: a1, a2, b, c, d1, d2, e, f, g1, g2, h, j, k, l, m, n
+/// - there is no decimal point
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glyphs16 {
+    pub unknown: (u8, u8), // shown for characters which can not be displayed
+}
+
+impl Glyphs16 {
+    pub const fn new() -> Self {
+        Glyphs16 { unknown: BLANK }
+    }
+}
+
+impl Default for Glyphs16 {
+    fn default() -> Self {
+        Glyphs16::new()
+    }
+}
+
+impl GlyphTable for Glyphs16 {
+    fn get_glyph(&self, c: char) -> Option<(u8, u8)> {
+        // implicit return
+        Glyphs14::new().get_glyph(c).map(convert_14_to_16)
+    }
+
+    fn get_unknown_glyph(&self) -> (u8, u8) {
+        self.unknown
+    }
+}
+
+// ========================================================================
+// user-defined glyphs
+// ========================================================================
+
+/// user-defined glyphs on top of another glyph table
+/// - the overrides take precedence over the base table
+/// - 'unknown' replaces the base table's unknown glyph (if set)
+///
+/// usage:
+/// ```TEXT
+///
+/// static GLYPHS: LayeredGlyphs<Glyphs7> = LayeredGlyphs {
+///     overrides: &[
+///         ('K', (0b0111_0101, 0b0000_0000)),
+///         ('M', (0b0011_0111, 0b0000_0000)),
+///     ],
+///     base: Glyphs7::new(),
+///     unknown: Some((0b0100_0000, 0b0000_0000)), // '-'
+/// };
+///
+/// let sd = Segment7x4::new(GLYPHS, 0, BlinkRate::NoBlink, 15)?;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayeredGlyphs<G> {
+    pub overrides: &'static [(char, (u8, u8))],
+    pub base: G,
+    pub unknown: Option<(u8, u8)>,
+}
+
+impl<G> LayeredGlyphs<G> {
+    pub const fn new(overrides: &'static [(char, (u8, u8))], base: G) -> Self {
+        LayeredGlyphs {
+            overrides,
+            base,
+            unknown: None,
+        }
+    }
+}
+
+impl<G> GlyphTable for LayeredGlyphs<G>
+where
+    G: GlyphTable,
+{
+    fn get_glyph(&self, c: char) -> Option<(u8, u8)> {
+        match self.overrides.iter().find(|(key, _)| *key == c) {
+            Some((_, glyph)) => Some(*glyph),
+            None => self.base.get_glyph(c),
+        }
+    }
+
+    fn get_unknown_glyph(&self) -> (u8, u8) {
+        match self.unknown {
+            Some(glyph) => glyph,
+            None => self.base.get_unknown_glyph(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+// helper functions
+// ------------------------------------------------------------------------
+
+/// approximate characters with diacritics by their base letter
+/// - returns 'None' if there is no approximation
+fn convert_to_base_letter(c: char) -> Option<char> {
+    let base = match c {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'Ç' => 'C',
+        'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'Ñ' => 'N',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        _ => return None,
+    };
+
+    // implicit return
+    Some(base)
+}
+
+/// split the top and bottom segments of a 14-segment glyph
+/// - 14 segments: a, b, c, d, e, f, g1, g2, h, j, k, l, m, n, dp
+/// - 16 segments: a1, a2, b, c, d1, d2, e, f, g1, g2, h, j, k, l, m, n
+fn convert_14_to_16(glyph: (u8, u8)) -> (u8, u8) {
+    let pattern14 = glyph.0 as u16 | (glyph.1 as u16) << 8;
+    let a = pattern14 & 0b0001;
+    let d = pattern14 >> 3 & 0b0001;
+
+    let pattern16 = a | a << 1 // a1, a2
+        | (pattern14 >> 1 & 0b0011) << 2 // b, c
+        | d << 4 | d << 5 // d1, d2
+        | (pattern14 >> 4 & 0b1111) << 6 // e, f, g1, g2
+        | (pattern14 >> 8 & 0b0011_1111) << 10; // h, j, k, l, m, n
+
+    // implicit return
+    (pattern16 as u8, (pattern16 >> 8) as u8)
+}
+
+#[test]
+fn convert_14_to_16_pass() {
+    let computed = [
+        convert_14_to_16(lookup_14('A').unwrap()),
+        convert_14_to_16(lookup_14('I').unwrap()),
+        convert_14_to_16(lookup_14('.').unwrap()),
+    ];
+    let expected = [
+        (0b1100_1111, 0b0000_0011), // a1, a2, b, c, e, f, g1, g2
+        (0b0000_0000, 0b0100_1000), // j, m
+        (0b0000_0000, 0b0000_0000), // no decimal point
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn glyphs7_lower_case() {
+    let glyphs = Glyphs7::new();
+
+    let computed = ['c', 'o', 'b', 'k'].map(|c| glyphs.get_glyph(c));
+    let expected = [
+        Some((0b0101_1000, 0b0000_0000)),
+        lookup_7('O'),
+        lookup_7('B'),
+        None,
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn glyphs_base_letter() {
+    let computed = [
+        Glyphs7::new().get_glyph('Ä'),
+        Glyphs7::new().get_glyph('ü'),
+        Glyphs14::new().get_glyph('Ö'),
+        Glyphs14::new().get_glyph('ß'),
+    ];
+    let expected = [
+        lookup_7('A'),
+        Glyphs7::new().get_glyph('u'),
+        lookup_14('O'),
+        None,
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn layered_glyphs_static() {
+    static GLYPHS: LayeredGlyphs<LayeredGlyphs<Glyphs7>> = LayeredGlyphs {
+        overrides: &[('A', (0b0000_0001, 0b0000_0000))],
+        base: LayeredGlyphs::new(&[('K', (0b0111_0101, 0b0000_0000))], Glyphs7::new()),
+        unknown: Some((0b0100_0000, 0b0000_0000)),
+    };
+
+    let computed = ['A', 'K', 'L', 'W'].map(|c| GLYPHS.convert(c));
+    let expected = [
+        (0b0000_0001, 0b0000_0000),
+        (0b0111_0101, 0b0000_0000),
+        lookup_7('L').unwrap(),
+        (0b0100_0000, 0b0000_0000),
+    ];
+
+    assert_eq!(computed, expected);
+}
@@ -0,0 +1,186 @@
+/*
+    embedded-graphics support for the LED matrices
+    (requires the feature 'embedded-graphics')
+
+    Drawing only modifies the in-memory framebuffer. The bus is not
+    touched until flush() is called.
+*/
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Size};
+use embedded_graphics_core::pixelcolor::raw::{RawData, RawU2};
+use embedded_graphics_core::pixelcolor::{BinaryColor, PixelColor};
+use embedded_graphics_core::Pixel;
+
+use crate::ht16k33::{BicolorMatrix8x8, LedColor, Matrix};
+
+// ------------------------------------------------------------------------
+// single color matrix
+// ------------------------------------------------------------------------
+
+impl<const WIDTH: usize> OriginDimensions for Matrix<WIDTH> {
+    /// the size after applying the rotation
+    fn size(&self) -> Size {
+        Size::new(self.get_width() as u32, self.get_height() as u32)
+    }
+}
+
+impl<const WIDTH: usize> DrawTarget for Matrix<WIDTH> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.draw_pixel(point.x, point.y, color.is_on());
+        }
+
+        // implicit return
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        match color {
+            BinaryColor::Off => Matrix::clear(self),
+            BinaryColor::On => self.fill(),
+        }
+
+        // implicit return
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------
+// bicolor matrix
+// ------------------------------------------------------------------------
+
+impl PixelColor for LedColor {
+    type Raw = RawU2;
+}
+
+impl From<RawU2> for LedColor {
+    fn from(raw: RawU2) -> Self {
+        // bit 0: green, bit 1: red
+        match raw.into_inner() {
+            0b01 => LedColor::Green,
+            0b10 => LedColor::Red,
+            0b11 => LedColor::Yellow,
+            _ => LedColor::Off,
+        }
+    }
+}
+
+impl From<LedColor> for RawU2 {
+    fn from(color: LedColor) -> Self {
+        // bit 0: green, bit 1: red
+        match color {
+            LedColor::Off => RawU2::new(0b00),
+            LedColor::Green => RawU2::new(0b01),
+            LedColor::Red => RawU2::new(0b10),
+            LedColor::Yellow => RawU2::new(0b11),
+        }
+    }
+}
+
+impl From<BinaryColor> for LedColor {
+    /// 'On' is mapped to 'Green'
+    fn from(color: BinaryColor) -> Self {
+        match color {
+            BinaryColor::Off => LedColor::Off,
+            BinaryColor::On => LedColor::Green,
+        }
+    }
+}
+
+impl OriginDimensions for BicolorMatrix8x8 {
+    fn size(&self) -> Size {
+        Size::new(self.get_width() as u32, self.get_height() as u32)
+    }
+}
+
+impl DrawTarget for BicolorMatrix8x8 {
+    type Color = LedColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.set_pixel(point.x, point.y, color);
+        }
+
+        // implicit return
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill(color);
+
+        // implicit return
+        Ok(())
+    }
+}
+
+#[test]
+fn matrix_draw_iter() {
+    use embedded_graphics_core::geometry::Point;
+
+    let mut matrix = crate::ht16k33::Matrix8x8::new(0).unwrap();
+    let pixels = [
+        Pixel(Point::new(0, 0), BinaryColor::On),
+        Pixel(Point::new(1, 0), BinaryColor::On),
+        Pixel(Point::new(1, 0), BinaryColor::Off),
+        Pixel(Point::new(9, 0), BinaryColor::On),
+    ];
+    let _ = matrix.draw_iter(pixels);
+
+    let computed = matrix.get_buffer()[0];
+    let expected = 0b0000_0001;
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn matrix_size() {
+    let mut matrix = crate::ht16k33::Matrix16x8::new(0).unwrap();
+    matrix.rotation = crate::ht16k33::Rotation::Rotate90;
+
+    let computed = matrix.size();
+    let expected = Size::new(8, 16);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn bicolor_matrix_clear() {
+    let mut matrix = BicolorMatrix8x8::new(0).unwrap();
+    let _ = DrawTarget::clear(&mut matrix, LedColor::Red);
+
+    let computed = matrix.get_buffer();
+    let expected = [
+        0x00, 0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00,
+        0xFF,
+    ];
+
+    assert_eq!(computed, &expected);
+}
+
+#[test]
+fn led_color_raw() {
+    let colors = [
+        LedColor::Off,
+        LedColor::Green,
+        LedColor::Red,
+        LedColor::Yellow,
+    ];
+    for color in colors {
+        let raw: RawU2 = color.into();
+
+        assert_eq!(LedColor::from(raw), color);
+    }
+}
@@ -0,0 +1,338 @@
+/*
+    HT16K33 commands
+
+    Every command is a single byte, the upper nibble selects the command
+    and the lower nibble holds the data. The bits marked as "don't care"
+    in the data sheet are ignored when a command byte is decoded.
+
+    | command                    | byte      | data                      |
+    |:---------------------------|:---------:|:--------------------------|
+    | display data address       | 0x00-0x0F | address pointer           |
+    | system setup               | 0x20-0x2F | oscillator (bit 0)        |
+    | key data address           | 0x40-0x45 | address pointer           |
+    | INT flag address           | 0x60-0x6F |                           |
+    | display setup              | 0x80-0x8F | display (bit 0), blinking |
+    | ROW/INT set                | 0xA0-0xAF | output, active level      |
+    | dimming set                | 0xE0-0xEF | duty cycle                |
+*/
+
+use crate::ht16k33::ConfigurationError;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the system oscillator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Oscillator {
+    Off = 0b0000, // standby mode
+    On = 0b0001,  // normal operation mode
+}
+
+/// the display setup (display on/off and blinking frequency)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlinkRate {
+    DisplayOff = 0b0000,  // the blinking frequency is ignored
+    NoBlink = 0b0001,     // display on, no blinking
+    Blink2Hz = 0b0011,    // display on, blinking with 2Hz
+    Blink1Hz = 0b0101,    // display on, blinking with 1Hz
+    BlinkHalfHz = 0b0111, // display on, blinking with 0.5Hz
+}
+
+/// the function of the ROW/INT output pin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RowIntMode {
+    Row = 0b0000,           // ROW driver output
+    IntActiveLow = 0b0001,  // INT output, active low
+    IntActiveHigh = 0b0011, // INT output, active high
+}
+
+/// the display's duty cycle (1/16 to 16/16)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dimming {
+    Duty1 = 0b0000,
+    Duty2 = 0b0001,
+    Duty3 = 0b0010,
+    Duty4 = 0b0011,
+    Duty5 = 0b0100,
+    Duty6 = 0b0101,
+    Duty7 = 0b0110,
+    Duty8 = 0b0111,
+    Duty9 = 0b1000,
+    Duty10 = 0b1001,
+    Duty11 = 0b1010,
+    Duty12 = 0b1011,
+    Duty13 = 0b1100,
+    Duty14 = 0b1101,
+    Duty15 = 0b1110,
+    Duty16 = 0b1111, // (default)
+}
+
+impl Dimming {
+    /// the brightness level (range: 0 ≤ x ≤ 15)
+    pub const fn get_level(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// convert a brightness level (range: 0 ≤ x ≤ 15)
+impl TryFrom<u8> for Dimming {
+    type Error = ConfigurationError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        let dimming = match value {
+            0 => Dimming::Duty1,
+            1 => Dimming::Duty2,
+            2 => Dimming::Duty3,
+            3 => Dimming::Duty4,
+            4 => Dimming::Duty5,
+            5 => Dimming::Duty6,
+            6 => Dimming::Duty7,
+            7 => Dimming::Duty8,
+            8 => Dimming::Duty9,
+            9 => Dimming::Duty10,
+            10 => Dimming::Duty11,
+            11 => Dimming::Duty12,
+            12 => Dimming::Duty13,
+            13 => Dimming::Duty14,
+            14 => Dimming::Duty15,
+            15 => Dimming::Duty16,
+            _ => return Err(ConfigurationError::InvalidBrightnessLevel(value)),
+        };
+
+        // implicit return
+        Ok(dimming)
+    }
+}
+
+/// a validated display data RAM address (range: 0x00 ≤ x ≤ 0x0F)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplayRamAddress(u8);
+
+impl DisplayRamAddress {
+    /// create an address which is known at compile time
+    /// (an invalid value fails to compile)
+    pub const fn new<const ADDRESS: u8>() -> Self {
+        const {
+            assert!(
+                ADDRESS <= 0x0F,
+                "Display RAM address must be in range 0x00 ≤ x ≤ 0x0F!"
+            )
+        };
+
+        // implicit return
+        DisplayRamAddress(ADDRESS)
+    }
+
+    pub const fn get_value(&self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for DisplayRamAddress {
+    type Error = ConfigurationError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value <= 0x0F {
+            Ok(DisplayRamAddress(value))
+        } else {
+            Err(ConfigurationError::InvalidRamAddress(value))
+        }
+    }
+}
+
+/// a validated key data RAM address (range: 0x00 ≤ x ≤ 0x05)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyRamAddress(u8);
+
+impl KeyRamAddress {
+    /// create an address which is known at compile time
+    /// (an invalid value fails to compile)
+    pub const fn new<const ADDRESS: u8>() -> Self {
+        const {
+            assert!(
+                ADDRESS <= 0x05,
+                "Key RAM address must be in range 0x00 ≤ x ≤ 0x05!"
+            )
+        };
+
+        // implicit return
+        KeyRamAddress(ADDRESS)
+    }
+
+    pub const fn get_value(&self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for KeyRamAddress {
+    type Error = ConfigurationError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value <= 0x05 {
+            Ok(KeyRamAddress(value))
+        } else {
+            Err(ConfigurationError::InvalidRamAddress(value))
+        }
+    }
+}
+
+/// a single command byte
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// set the display data address pointer
+    DisplayDataAddress(DisplayRamAddress),
+    SystemSetup(Oscillator),
+    /// set the key data address pointer
+    KeyDataAddress(KeyRamAddress),
+    IntFlagAddress,
+    DisplaySetup(BlinkRate),
+    RowIntSet(RowIntMode),
+    DimmingSet(Dimming),
+}
+
+impl Command {
+    /// the command byte as sent to the device
+    pub fn encode(&self) -> u8 {
+        match self {
+            Command::DisplayDataAddress(address) => address.get_value(),
+            Command::SystemSetup(oscillator) => 0x20 | *oscillator as u8,
+            Command::KeyDataAddress(address) => 0x40 | address.get_value(),
+            Command::IntFlagAddress => 0x60,
+            Command::DisplaySetup(blink_rate) => 0x80 | *blink_rate as u8,
+            Command::RowIntSet(mode) => 0xA0 | *mode as u8,
+            Command::DimmingSet(dimming) => 0xE0 | *dimming as u8,
+        }
+    }
+
+    /// decode a command byte
+    /// - returns 'None' if the byte is not a documented command
+    /// - "don't care" bits are ignored
+    pub fn decode(byte: u8) -> Option<Command> {
+        let data = byte & 0x0F;
+        let command = match byte & 0xF0 {
+            0x00 => Command::DisplayDataAddress(DisplayRamAddress(data)),
+            0x20 => match data & 0b0001 {
+                0 => Command::SystemSetup(Oscillator::Off),
+                _ => Command::SystemSetup(Oscillator::On),
+            },
+            0x40 => Command::KeyDataAddress(KeyRamAddress::try_from(data).ok()?),
+            0x60 => Command::IntFlagAddress,
+            // the blinking frequency is ignored while the display is off
+            0x80 => match data & 0b0111 {
+                0b0001 => Command::DisplaySetup(BlinkRate::NoBlink),
+                0b0011 => Command::DisplaySetup(BlinkRate::Blink2Hz),
+                0b0101 => Command::DisplaySetup(BlinkRate::Blink1Hz),
+                0b0111 => Command::DisplaySetup(BlinkRate::BlinkHalfHz),
+                _ => Command::DisplaySetup(BlinkRate::DisplayOff),
+            },
+            // the active level is ignored while the pin is a ROW output
+            0xA0 => match data & 0b0011 {
+                0b0001 => Command::RowIntSet(RowIntMode::IntActiveLow),
+                0b0011 => Command::RowIntSet(RowIntMode::IntActiveHigh),
+                _ => Command::RowIntSet(RowIntMode::Row),
+            },
+            0xE0 => Command::DimmingSet(Dimming::try_from(data).ok()?),
+            _ => return None,
+        };
+
+        // implicit return
+        Some(command)
+    }
+}
+
+#[test]
+fn encode_command() {
+    let computed = [
+        Command::DisplayDataAddress(DisplayRamAddress::new::<0x04>()).encode(),
+        Command::SystemSetup(Oscillator::On).encode(),
+        Command::KeyDataAddress(KeyRamAddress::new::<0x00>()).encode(),
+        Command::IntFlagAddress.encode(),
+        Command::DisplaySetup(BlinkRate::Blink1Hz).encode(),
+        Command::RowIntSet(RowIntMode::IntActiveHigh).encode(),
+        Command::DimmingSet(Dimming::Duty16).encode(),
+    ];
+    let expected = [0x04, 0x21, 0x40, 0x60, 0x85, 0xA3, 0xEF];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn decode_command() {
+    // every valid command byte survives a round trip
+    for byte in 0x00..=0xFF {
+        if let Some(command) = Command::decode(byte) {
+            assert_eq!(Command::decode(command.encode()), Some(command));
+        }
+    }
+
+    let computed = [
+        Command::decode(0x2E),
+        Command::decode(0x46),
+        Command::decode(0x82),
+        Command::decode(0x8B),
+        Command::decode(0xA2),
+        Command::decode(0xD9),
+    ];
+    let expected = [
+        Some(Command::SystemSetup(Oscillator::Off)),
+        None,
+        Some(Command::DisplaySetup(BlinkRate::DisplayOff)),
+        Some(Command::DisplaySetup(BlinkRate::Blink2Hz)),
+        Some(Command::RowIntSet(RowIntMode::Row)),
+        None, // test mode (HOLTEK use only)
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn encode_address_out_of_range() {
+    // out of range addresses are rejected instead of being masked/clamped,
+    // every accepted address survives a round trip
+    for value in 0x00..=0xFF {
+        match DisplayRamAddress::try_from(value) {
+            Ok(address) => {
+                let command = Command::DisplayDataAddress(address);
+                assert_eq!(Command::decode(command.encode()), Some(command));
+            }
+            Err(error) => assert_eq!(error, ConfigurationError::InvalidRamAddress(value)),
+        }
+        match KeyRamAddress::try_from(value) {
+            Ok(address) => {
+                let command = Command::KeyDataAddress(address);
+                assert_eq!(Command::decode(command.encode()), Some(command));
+            }
+            Err(error) => assert_eq!(error, ConfigurationError::InvalidRamAddress(value)),
+        }
+    }
+
+    let computed = [
+        DisplayRamAddress::try_from(0x10).is_ok(),
+        KeyRamAddress::try_from(0x05).is_ok(),
+        KeyRamAddress::try_from(0x06).is_ok(),
+    ];
+    let expected = [false, true, false];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn convert_dimming() {
+    let computed = [
+        Dimming::try_from(0),
+        Dimming::try_from(15),
+        Dimming::try_from(16),
+    ];
+    let expected = [
+        Ok(Dimming::Duty1),
+        Ok(Dimming::Duty16),
+        Err(ConfigurationError::InvalidBrightnessLevel(16)),
+    ];
+
+    assert_eq!(computed, expected);
+    assert_eq!(Dimming::Duty8.get_level(), 7);
+    assert_eq!(
+        Dimming::try_from(Dimming::Duty8.get_level()),
+        Ok(Dimming::Duty8)
+    );
+}
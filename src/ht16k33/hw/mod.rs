@@ -10,9 +10,27 @@
 
 use core::iter::Iterator;
 
+use crate::ht16k33::ConfigurationError;
+
+mod command;
+
+pub use command::{
+    BlinkRate, Command, Dimming, DisplayRamAddress, KeyRamAddress, Oscillator, RowIntMode,
+};
+
 #[allow(unused_imports)]
 use log::{debug, error, info, warn};
 
+/// send a single command byte
+pub fn send_command<Ibd>(ibd: &mut Ibd, da: u8, command: Command)
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let value = command.encode();
+    debug!("Sending {0:?} ({1:#04X}) to {2:#04X}.", command, value, da);
+    ibd.write_byte(da, value);
+}
+
 // ------------------------------------------------------------------------
 // display data address pointer (0x00)
 // ------------------------------------------------------------------------
@@ -30,6 +48,41 @@ where
     ibd.write_bytes(da, &buffer);
 }
 
+/// write a part of the display data RAM
+/// - 'start' is the first address, the address pointer is incremented
+///   automatically
+/// - the values must fit into the remaining display data RAM
+///   (at most 16 - 'start' bytes)
+pub fn set_display_data_range<Ibd>(
+    ibd: &mut Ibd,
+    da: u8,
+    start: DisplayRamAddress,
+    values: &[u8],
+) -> Result<(), ConfigurationError>
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let first = start.get_value() as usize;
+    let length = values.len();
+    if length > 16 - first {
+        error!("Display data range must be within 0x00 ≤ x ≤ 0x0F");
+        return Err(ConfigurationError::InvalidDataLength(length));
+    }
+
+    let mut buffer: [u8; 17] = [0x00; 17];
+    buffer[0] = Command::DisplayDataAddress(start).encode();
+    buffer[1..=length].copy_from_slice(values);
+
+    debug!(
+        "Setting data on {0:#04X} ({1} bytes starting at {2:#04X}).",
+        da, length, first
+    );
+    ibd.write_bytes(da, &buffer[..=length]);
+
+    // implicit return
+    Ok(())
+}
+
 // ------------------------------------------------------------------------
 // system setup register (0x20)
 // ------------------------------------------------------------------------
@@ -39,25 +92,14 @@ where
 // - accept input commands
 // - write data to any register except the system setup register
 
-// enable/disable the internal system oscillator
-// - 0: turn off system oscillator (standby mode)
-// - 1: turn on system oscillator (normal operation mode)
-pub fn set_oscillator_mode<Ibd>(ibd: &mut Ibd, da: u8, value: u8) -> bool
+/// enable/disable the internal system oscillator
+/// - Off: turn off system oscillator (standby mode)
+/// - On: turn on system oscillator (normal operation mode)
+pub fn set_oscillator_mode<Ibd>(ibd: &mut Ibd, da: u8, oscillator: Oscillator)
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    if value <= 1 {
-        let value = 0x20 | value;
-        debug!(
-            "Setting oscillator mode on {0:#04X} to {1:#04X}.",
-            da, value
-        );
-        ibd.write_byte(da, value);
-        true
-    } else {
-        error!("Oscillator mode must be in range 0 ≤ x ≤ 1");
-        false
-    }
+    send_command(ibd, da, Command::SystemSetup(oscillator));
 }
 
 // ------------------------------------------------------------------------
@@ -71,7 +113,23 @@ where
 // - if the key data register address (An) is 0x40~0x45, after reaching the
 //   memory location 0x45, the pointer will reset to 0x40
 
-// <not implemented>
+/// read the key data RAM (0x40..0x45) in a single transaction
+/// - 2 bytes per KS line (KS0, KS1, KS2), little endian
+/// - bit 0..12 represent the ROW lines (K1..K13)
+/// - reading the key data RAM clears the INT flag
+pub fn get_key_data<Ibd>(ibd: &mut Ibd, da: u8) -> [u8; 6]
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let mut buffer: [u8; 6] = [0x00; 6];
+    let start = Command::KeyDataAddress(KeyRamAddress::new::<0x00>());
+    ibd.read_register_block(da, start.encode(), &mut buffer);
+
+    debug!("Key data on {0:#04X}: {1:02X?}", da, buffer);
+
+    // implicit return
+    buffer
+}
 
 // ------------------------------------------------------------------------
 // INT flag address pointer (0x60)
@@ -80,32 +138,32 @@ where
 // scan cycles, this int flag bit goes to a high level and remains at a
 // high level until all key data has been read.
 
-// <not implemented>
+/// read the INT flag
+/// - returns 'true' if a key press was detected since the last read of
+///   the key data RAM
+pub fn get_int_flag<Ibd>(ibd: &mut Ibd, da: u8) -> bool
+where
+    Ibd: crate::traits::I2cBusDevice,
+{
+    let value = ibd.read_register_as_byte(da, Command::IntFlagAddress.encode());
+
+    // implicit return
+    value != 0x00
+}
 
 // ------------------------------------------------------------------------
 // display setup register (0x80)
 // ------------------------------------------------------------------------
 
 /// set the display's blink rate
-/// - 1: no blinking
-/// - 3: 2Hz
-/// - 5: 1Hz
-/// - 7: 0.5Hz
-///
-/// _(the even values 0, 2, 4 and 6 are not used)_
-pub fn set_blink_rate<Ibd>(ibd: &mut Ibd, da: u8, value: u8) -> bool
+/// - DisplayOff: the display is turned off
+/// - NoBlink: no blinking
+/// - Blink2Hz, Blink1Hz, BlinkHalfHz: blinking
+pub fn set_blink_rate<Ibd>(ibd: &mut Ibd, da: u8, blink_rate: BlinkRate)
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    if value <= 15 {
-        let value = 0x80 | value;
-        debug!("Setting blink rate on {0:#04X} to {1:#04X}.", da, value);
-        ibd.write_byte(da, value);
-        true
-    } else {
-        error!("Blink rate must be in range 0 ≤ x ≤ 15");
-        false
-    }
+    send_command(ibd, da, Command::DisplaySetup(blink_rate));
 }
 
 // ------------------------------------------------------------------------
@@ -113,22 +171,14 @@ where
 // ------------------------------------------------------------------------
 
 /// defines INT/ROW output pin select and INT pin output active level status
-/// - 0: INT/ROW output pin is set to ROW driver output
-/// - 1: INT/ROW output pin is set to INT output (active low)
-/// - 3: INT/ROW output pin is set to INT output (active high)
-///
-/// _(the value 2 is treated as 0)_
-pub fn set_output_select<Ibd>(ibd: &mut Ibd, da: u8, value: u8) -> bool
+/// - Row: INT/ROW output pin is set to ROW driver output
+/// - IntActiveLow: INT/ROW output pin is set to INT output (active low)
+/// - IntActiveHigh: INT/ROW output pin is set to INT output (active high)
+pub fn set_output_select<Ibd>(ibd: &mut Ibd, da: u8, mode: RowIntMode)
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    if value <= 3 {
-        ibd.write_byte(da, 0xA0 | value);
-        true
-    } else {
-        error!("Output pin select must be in range 0 ≤ x ≤ 3");
-        false
-    }
+    send_command(ibd, da, Command::RowIntSet(mode));
 }
 
 // ------------------------------------------------------------------------
@@ -144,21 +194,10 @@ where
 
 /// set the display's brightness level
 ///
-/// brightness level is graduated from 0 (6%) to 15 (100%)
-pub fn set_brightness_level<Ibd>(ibd: &mut Ibd, da: u8, value: u8) -> bool
+/// brightness level is graduated from Duty1 (1/16, 6%) to Duty16 (100%)
+pub fn set_brightness_level<Ibd>(ibd: &mut Ibd, da: u8, dimming: Dimming)
 where
     Ibd: crate::traits::I2cBusDevice,
 {
-    if value <= 15 {
-        let value = 0xE0 | value;
-        debug!(
-            "Setting brightness level on {0:#04X} to {1:#04X}.",
-            da, value
-        );
-        ibd.write_byte(da, value);
-        true
-    } else {
-        error!("Brightness level must be in range 0 ≤ x ≤ 15");
-        false
-    }
+    send_command(ibd, da, Command::DimmingSet(dimming));
 }
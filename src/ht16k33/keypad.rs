@@ -0,0 +1,230 @@
+/*
+    key matrix (13 ROW lines × 3 KS lines)
+
+    The HT16K33 scans the key matrix on its own and stores the result in
+    the key data RAM. Each key is identified by its key ID:
+
+        key ID = 13 * KS + ROW   (KS: 0..2, ROW: 0..12 for K1..K13)
+
+    e.g. the key connected to KS1 and K4 has the key ID 13 * 1 + 3 = 16
+*/
+
+use crate::ht16k33::hw::{self, Oscillator, RowIntMode};
+use crate::ht16k33::{ConfigurationError, DeviceId};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// number of keys supported by the key matrix
+pub static KEY_COUNT: u8 = 39;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyEvent {
+    Pressed(u8),  // key ID
+    Released(u8), // key ID
+}
+
+/// the changes between two debounced scans
+/// (iterates over the key events in order of their key ID)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyEvents {
+    pressed: u64,
+    released: u64,
+    key: u8,
+}
+
+impl KeyEvents {
+    fn new(previous: u64, current: u64) -> Self {
+        KeyEvents {
+            pressed: current & !previous,
+            released: previous & !current,
+            key: 0,
+        }
+    }
+
+    /// returns 'true' if no key was pressed or released
+    pub fn is_empty(&self) -> bool {
+        // implicit return
+        (self.pressed | self.released) >> self.key == 0
+    }
+}
+
+impl Iterator for KeyEvents {
+    type Item = KeyEvent;
+
+    fn next(&mut self) -> Option<KeyEvent> {
+        while self.key < KEY_COUNT {
+            let key = self.key;
+            let mask = 1u64 << key;
+            self.key += 1;
+            if self.pressed & mask != 0 {
+                return Some(KeyEvent::Pressed(key));
+            }
+            if self.released & mask != 0 {
+                return Some(KeyEvent::Released(key));
+            }
+        }
+
+        // implicit return
+        None
+    }
+}
+
+/// keypad wired to the HT16K33's key matrix
+/// - a change is accepted after it was seen in 'debounce_scans'
+///   consecutive scans
+/// - the system oscillator must be running (see enable())
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut keypad = Keypad::new(0, 2)?;
+/// keypad.enable(&mut ibd);
+/// loop {
+///     for event in keypad.scan(&mut ibd) {
+///         match event {
+///             KeyEvent::Pressed(key) => info!("key {key} pressed"),
+///             KeyEvent::Released(key) => info!("key {key} released"),
+///         }
+///     }
+///     ibd.sleep_ms(20);
+/// }
+/// ```
+pub struct Keypad {
+    pub did: DeviceId,
+    debounce_scans: u8,
+    state: u64,     // debounced state (1 bit per key ID)
+    candidate: u64, // most recently scanned state
+    seen: u8,       // number of consecutive scans with the candidate state
+}
+
+impl Keypad {
+    /// create a new keypad
+    /// - device ID must be in range 0 ≤ x ≤ 7
+    /// - debounce scans must be in range 1 ≤ x ≤ 255 (use 1 to accept each
+    ///   change right away)
+    pub fn new(did: u8, debounce_scans: u8) -> Result<Self, ConfigurationError> {
+        if debounce_scans == 0 {
+            return Err(ConfigurationError::InvalidDebounceScans(debounce_scans));
+        }
+
+        let keypad = Keypad {
+            did: DeviceId::try_from(did)?,
+            debounce_scans,
+            state: 0,
+            candidate: 0,
+            seen: 0,
+        };
+
+        // implicit return
+        Ok(keypad)
+    }
+
+    /// the number of consecutive scans required to accept a change
+    pub fn get_debounce_scans(&self) -> u8 {
+        self.debounce_scans
+    }
+
+    /// turn on the system oscillator (required for key scanning)
+    pub fn enable<Ibd>(&self, ibd: &mut Ibd)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.did.get_address();
+        hw::set_oscillator_mode(ibd, da, Oscillator::On);
+    }
+
+    /// configure the ROW/INT pin
+    /// - 'Row' uses the pin as ROW driver output (e.g. for a display)
+    /// - otherwise the pin signals key presses and get_int_flag() can be
+    ///   skipped
+    pub fn set_interrupt_mode<Ibd>(&self, ibd: &mut Ibd, mode: RowIntMode)
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.did.get_address();
+        hw::set_output_select(ibd, da, mode);
+    }
+
+    /// returns 'true' if a key press was detected since the last scan
+    pub fn get_int_flag<Ibd>(&self, ibd: &mut Ibd) -> bool
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.did.get_address();
+
+        // implicit return
+        hw::get_int_flag(ibd, da)
+    }
+
+    /// read the key data RAM and return the key events
+    pub fn scan<Ibd>(&mut self, ibd: &mut Ibd) -> KeyEvents
+    where
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let da = self.did.get_address();
+        let key_data = hw::get_key_data(ibd, da);
+
+        // implicit return
+        self.update(convert_key_data(&key_data))
+    }
+
+    /// feed a scanned state into the debouncer and return the key events
+    /// (1 bit per key ID)
+    pub fn update(&mut self, keys: u64) -> KeyEvents {
+        if keys == self.candidate {
+            self.seen = self.seen.saturating_add(1);
+        } else {
+            self.candidate = keys;
+            self.seen = 1;
+        }
+
+        let previous = self.state;
+        if self.seen >= self.debounce_scans {
+            self.state = self.candidate;
+        }
+
+        // implicit return
+        KeyEvents::new(previous, self.state)
+    }
+
+    /// returns 'true' if the key is currently pressed (debounced)
+    pub fn is_pressed(&self, key: u8) -> bool {
+        // implicit return
+        key < KEY_COUNT && self.state & (1u64 << key) != 0
+    }
+
+    /// the currently pressed keys (debounced, 1 bit per key ID)
+    pub fn get_pressed_keys(&self) -> u64 {
+        // implicit return
+        self.state
+    }
+}
+
+/// convert the key data RAM into a bitmask (1 bit per key ID)
+pub fn convert_key_data(key_data: &[u8; 6]) -> u64 {
+    let mut keys: u64 = 0;
+    for ks in 0..3 {
+        let rows = u16::from_le_bytes([key_data[2 * ks], key_data[2 * ks + 1]]) & 0x1FFF;
+        keys |= (rows as u64) << (13 * ks);
+    }
+
+    // implicit return
+    keys
+}
+
+#[test]
+fn convert_key_data_pass() {
+    let computed = convert_key_data(&[0x01, 0x00, 0x08, 0x00, 0x00, 0x10]);
+    let expected = (1 << 0) | (1 << 16) | (1 << 38);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn convert_key_data_ignores_unused_bits() {
+    let computed = convert_key_data(&[0x00, 0xE0, 0x00, 0xE0, 0x00, 0xE0]);
+    let expected = 0;
+
+    assert_eq!(computed, expected);
+}
@@ -0,0 +1,292 @@
+/*
+    text layout for segmented displays
+
+    The layout engine decides which character ends up in which digit:
+    - a '.' is merged into the preceding digit (decimal point)
+    - characters with a dedicated indicator bit (e.g. ':', '\'' and '°'
+      on the 7-segment backpack) do not use a digit
+    - all other characters use one digit each
+
+    The wiring maps the logical segments of each digit to the display RAM.
+    Displays without indicators (e.g. the 14-segment backpack) show these
+    characters as regular characters instead.
+*/
+
+use crate::ht16k33::GlyphTable;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the result of laying out text on a display
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fit {
+    Complete,         // all characters are displayed
+    Truncated(usize), // only the first n characters are displayed
+}
+
+/// the maximum number of digits of a single display
+/// (each digit uses its own common line, the display RAM has 8 of them)
+pub const DISPLAY_MAX_DIGITS: usize = 8;
+
+/// the wiring of a segmented display: which display RAM bit (row, com)
+/// controls which segment
+///
+/// The display RAM has 8 common lines (com 0-7) with 16 rows each (row
+/// 0-15). A glyph is a 2-byte tuple, logical segment 's' is bit 's' of the
+/// first byte (s < 8) or bit 's - 8' of the second byte.
+///
+/// usage:
+/// ```TEXT
+///
+/// // digits on com 3, 2, 1, 0 (right to left), segment 's' on row 's'
+/// static WIRING: Wiring<4, 8> = Wiring::by_digit([3, 2, 1, 0], &[]);
+///
+/// // or map every segment individually
+/// static WIRING: Wiring<4, 8> = Wiring {
+///     segments: [[(0, 0), (1, 0), ...], ...],
+///     indicators: &[(':', (8, 0))],
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wiring<const DIGITS: usize, const SEGMENTS: usize> {
+    /// the (row, com) bit of each logical segment, per digit
+    pub segments: [[(u8, u8); SEGMENTS]; DIGITS],
+    /// characters shown on a dedicated (row, com) bit without using a
+    /// digit (e.g. colon)
+    pub indicators: &'static [(char, (u8, u8))],
+}
+
+impl<const DIGITS: usize, const SEGMENTS: usize> Wiring<DIGITS, SEGMENTS> {
+    /// each digit uses its own common line, segment 's' is wired to row 's'
+    /// (this is how most backpacks are wired)
+    pub const fn by_digit(coms: [u8; DIGITS], indicators: &'static [(char, (u8, u8))]) -> Self {
+        let mut segments = [[(0, 0); SEGMENTS]; DIGITS];
+        let mut digit = 0;
+        while digit < DIGITS {
+            let mut segment = 0;
+            while segment < SEGMENTS {
+                segments[digit][segment] = (segment as u8, coms[digit]);
+                segment += 1;
+            }
+            digit += 1;
+        }
+
+        // implicit return
+        Wiring {
+            segments,
+            indicators,
+        }
+    }
+
+    /// find the first (row, com) bit which does not exist
+    /// - returns 'None' if the wiring is valid
+    pub fn find_invalid_bit(&self) -> Option<(u8, u8)> {
+        let mut bits = self
+            .segments
+            .iter()
+            .flatten()
+            .chain(self.indicators.iter().map(|(_, bit)| bit));
+
+        // implicit return
+        bits.find(|(row, com)| *row > 15 || *com > 7).copied()
+    }
+
+    /// write the glyph into the provided digit (the digit's segments are
+    /// cleared first unless 'merge' is set)
+    fn set_digit(&self, buffer: &mut [u8; 16], digit: usize, glyph: (u8, u8), merge: bool) {
+        let pattern = glyph.0 as u32 | (glyph.1 as u32) << 8;
+        for (segment, (row, com)) in self.segments[digit].iter().enumerate() {
+            let is_on = segment < 16 && pattern & (1 << segment) != 0;
+            if is_on {
+                set_bit(buffer, *row, *com, true);
+            } else if !merge {
+                set_bit(buffer, *row, *com, false);
+            }
+        }
+    }
+
+    pub(crate) fn get_indicator(&self, c: char) -> Option<(u8, u8)> {
+        // implicit return
+        self.indicators
+            .iter()
+            .find(|(key, _)| *key == c)
+            .map(|(_, bit)| *bit)
+    }
+}
+
+/// set or clear a display RAM bit (bits which do not exist are ignored)
+fn set_bit(buffer: &mut [u8; 16], row: u8, com: u8, is_on: bool) {
+    if row > 15 || com > 7 {
+        return;
+    }
+
+    let idx = 2 * com as usize + row as usize / 8;
+    let mask = 1 << (row % 8);
+    if is_on {
+        buffer[idx] |= mask;
+    } else {
+        buffer[idx] &= !mask;
+    }
+}
+
+/// the 7-segment backpack (4 digits and a colon)
+/// - digit, digit, indicators (com 2), digit, digit
+/// - colon, degree and apostrophe are shown on the indicators
+pub static WIRING_7X4: Wiring<4, 8> = Wiring::by_digit(
+    [0, 1, 3, 4],
+    &[
+        (':', (1, 2)),  // center colon
+        ('°', (2, 2)),  // upper left dot (degree)
+        ('\'', (4, 2)), // apostrophe
+    ],
+);
+
+/// the 14-segment backpack (4 digits)
+/// - segments 0-13 and the decimal point (segment 14) on rows 0-14
+pub static WIRING_14X4: Wiring<4, 16> = Wiring::by_digit([0, 1, 2, 3], &[]);
+
+/// 8 digits of 7 segments and decimal point (common cathode)
+pub static WIRING_7X8: Wiring<8, 8> = Wiring::by_digit([0, 1, 2, 3, 4, 5, 6, 7], &[]);
+
+/// 8 digits of 16 segments (common cathode, no decimal point)
+pub static WIRING_16X8: Wiring<8, 16> = Wiring::by_digit([0, 1, 2, 3, 4, 5, 6, 7], &[]);
+
+/// convert the provided characters into a data buffer
+/// - stops at the first character which does not fit
+/// - the data buffer is not cleared, only the used digits are written
+pub(crate) fn layout_chars<I, G, const DIGITS: usize, const SEGMENTS: usize>(
+    chars: I,
+    wiring: &Wiring<DIGITS, SEGMENTS>,
+    glyphs: &G,
+    buffer: &mut [u8; 16],
+) -> Fit
+where
+    I: Iterator<Item = char>,
+    G: GlyphTable + ?Sized,
+{
+    let mut digit = 0; // number of used digits
+    let mut can_merge = false; // the previous digit can take a dot
+
+    for (count, c) in chars.enumerate() {
+        if c == '.' && can_merge {
+            wiring.set_digit(buffer, digit - 1, glyphs.convert(c), true);
+            can_merge = false;
+            continue;
+        }
+        if let Some((row, com)) = wiring.get_indicator(c) {
+            set_bit(buffer, row, com, true);
+            can_merge = false;
+            continue;
+        }
+        if digit == DIGITS {
+            return Fit::Truncated(count);
+        }
+
+        wiring.set_digit(buffer, digit, glyphs.convert(c), false);
+        digit += 1;
+        can_merge = c != '.';
+    }
+
+    // implicit return
+    Fit::Complete
+}
+
+#[cfg(test)]
+fn convert_test(c: char) -> (u8, u8) {
+    // use the character's code as the first byte, and a marker for dots
+    match c {
+        '.' => (0x80, 0x01),
+        _ => (c as u8, 0x00),
+    }
+}
+
+#[test]
+fn layout_chars_dots() {
+    let mut buffer = [0u8; 16];
+
+    let computed = layout_chars("1.2.34".chars(), &WIRING_14X4, &convert_test, &mut buffer);
+    let expected = Fit::Complete;
+
+    assert_eq!(computed, expected);
+    assert_eq!(
+        buffer[..8],
+        [b'1' | 0x80, 0x01, b'2' | 0x80, 0x01, b'3', 0x00, b'4', 0x00]
+    );
+}
+
+#[test]
+fn layout_chars_repeated_dots() {
+    let mut buffer = [0u8; 16];
+
+    let computed = layout_chars("..1".chars(), &WIRING_14X4, &convert_test, &mut buffer);
+    let expected = Fit::Complete;
+
+    // dots which do not follow a digit use their own digit
+    assert_eq!(computed, expected);
+    assert_eq!(buffer[..6], [0x80, 0x01, 0x80, 0x01, b'1', 0x00]);
+}
+
+#[test]
+fn layout_chars_indicators() {
+    let mut buffer = [0u8; 16];
+
+    let computed = layout_chars("12:3'4°".chars(), &WIRING_7X4, &convert_test, &mut buffer);
+    let expected = Fit::Complete;
+
+    assert_eq!(computed, expected);
+    assert_eq!(
+        buffer[..10],
+        [
+            b'1',
+            0x00,
+            b'2',
+            0x00,
+            0b0001_0110,
+            0x00,
+            b'3',
+            0x00,
+            b'4',
+            0x00
+        ]
+    );
+}
+
+#[test]
+fn layout_chars_truncated() {
+    let mut buffer = [0u8; 16];
+
+    let computed = layout_chars("1234.:5".chars(), &WIRING_7X4, &convert_test, &mut buffer);
+    let expected = Fit::Truncated(6);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn layout_chars_custom_wiring() {
+    // 2 digits of 3 segments, wired in reverse order, indicator on row 8
+    static WIRING: Wiring<2, 3> = Wiring {
+        segments: [[(2, 1), (1, 1), (0, 1)], [(2, 0), (1, 0), (0, 0)]],
+        indicators: &[(':', (8, 0))],
+    };
+    let mut buffer = [0u8; 16];
+    buffer[0] = 0b1111_1111;
+
+    let computed = layout_chars("\u{1}:\u{6}".chars(), &WIRING, &convert_test, &mut buffer);
+    let expected = Fit::Complete;
+
+    // unused rows are not touched
+    assert_eq!(computed, expected);
+    assert_eq!(buffer[..4], [0b1111_1011, 0b0000_0001, 0b0000_0100, 0x00]);
+}
+
+#[test]
+fn wiring_by_digit() {
+    static WIRING: Wiring<2, 2> = Wiring::by_digit([5, 2], &[]);
+
+    let computed = WIRING.segments;
+    let expected = [[(0, 5), (1, 5)], [(0, 2), (1, 2)]];
+
+    assert_eq!(computed, expected);
+    assert_eq!(WIRING.find_invalid_bit(), None);
+    assert_eq!(WIRING_16X8.find_invalid_bit(), None);
+}
@@ -0,0 +1,328 @@
+/*
+    scrolling text for segmented displays
+
+    The marquee is driven by the caller, e.g. by calling tick() from a
+    timer or the main loop. It does not sleep and does not allocate.
+*/
+
+use crate::ht16k33::SegmentedDisplay;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollMode {
+    Left,   // text moves to the left, enters on the right
+    Right,  // text moves to the right, enters on the left
+    Bounce, // text moves back and forth (no blank digits)
+}
+
+/// scroll text which is longer than the display
+/// - the text moves by one digit per step (a dot which is merged into the
+///   preceding digit and the indicators move along with their digit)
+///
+/// usage:
+/// ```TEXT
+///
+/// let mut marquee = Marquee::new(&sd, "FAN FAILURE ZONE 3", ScrollMode::Left, 5);
+/// marquee.show(&sd, &mut ibd);
+/// loop {
+///     // called every 50ms, the text moves every 250ms
+///     if marquee.tick() {
+///         marquee.show(&sd, &mut ibd);
+///     }
+///     ibd.sleep_ms(50);
+/// }
+/// ```
+pub struct Marquee<'a> {
+    text: &'a str,
+    length: isize, // number of digits used by the text
+    width: isize,  // number of digits of the display
+    mode: ScrollMode,
+    ticks_per_step: u32,
+    ticks: u32,
+    position: isize,      // index of the leftmost digit (may be negative)
+    is_moving_back: bool, // direction for 'Bounce'
+}
+
+impl<'a> Marquee<'a> {
+    /// create a new marquee for the provided display
+    /// - the text is measured in digits of this display (e.g. the colon
+    ///   only uses a digit if the display has no colon indicator)
+    /// - 'ticks_per_step' is the number of ticks between two steps
+    ///   (use 1 to advance on each tick)
+    pub fn new<D>(display: &D, text: &'a str, mode: ScrollMode, ticks_per_step: u32) -> Self
+    where
+        D: SegmentedDisplay,
+    {
+        let length = digit_starts(text, |c| display.is_indicator(c)).count();
+
+        Marquee {
+            text,
+            length: length as isize,
+            width: display.get_digit_count().max(1) as isize,
+            mode,
+            ticks_per_step: ticks_per_step.max(1),
+            ticks: 0,
+            position: 0,
+            is_moving_back: false,
+        }
+    }
+
+    /// return to the initial position
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+        self.position = 0;
+        self.is_moving_back = false;
+    }
+
+    /// count a tick and advance the text if 'ticks_per_step' is reached
+    /// - returns 'true' if the text was moved
+    pub fn tick(&mut self) -> bool {
+        self.ticks += 1;
+        if self.ticks < self.ticks_per_step {
+            return false;
+        }
+        self.ticks = 0;
+
+        // implicit return
+        self.step()
+    }
+
+    /// advance the text by one digit
+    /// - returns 'true' if the text was moved
+    ///   (text which fits on the display never moves in 'Bounce' mode)
+    pub fn step(&mut self) -> bool {
+        match self.mode {
+            ScrollMode::Left => {
+                // after the last digit has left, show a blank display
+                // and let the text enter from the right
+                self.position += 1;
+                if self.position >= self.length {
+                    self.position = -self.width;
+                }
+            }
+            ScrollMode::Right => {
+                self.position -= 1;
+                if self.position < -self.width {
+                    self.position = self.length - 1;
+                }
+            }
+            ScrollMode::Bounce => {
+                let last = self.length - self.width;
+                if last <= 0 {
+                    return false;
+                }
+                if self.is_moving_back {
+                    self.position -= 1;
+                } else {
+                    self.position += 1;
+                }
+                if self.position <= 0 || self.position >= last {
+                    self.is_moving_back = !self.is_moving_back;
+                }
+            }
+        }
+
+        // implicit return
+        true
+    }
+
+    /// the part of the text which is currently visible
+    /// - returns the number of blank digits on the left and the text
+    ///   starting at the leftmost visible digit
+    /// - the text is not cut at the right edge, show() renders as many
+    ///   digits as the display has
+    pub fn get_window<D>(&self, display: &D) -> (usize, &'a str)
+    where
+        D: SegmentedDisplay,
+    {
+        // implicit return
+        self.window(|c| display.is_indicator(c))
+    }
+
+    fn window<F>(&self, is_indicator: F) -> (usize, &'a str)
+    where
+        F: Fn(char) -> bool,
+    {
+        let blanks = (-self.position).clamp(0, self.width) as usize;
+        if blanks == self.width as usize {
+            return (blanks, "");
+        }
+
+        let start = self.position.max(0) as usize;
+        let offset = digit_starts(self.text, is_indicator)
+            .nth(start)
+            .unwrap_or(self.text.len());
+
+        // implicit return
+        (blanks, &self.text[offset..])
+    }
+
+    /// display the visible part of the text
+    pub fn show<D, Ibd>(&self, display: &D, ibd: &mut Ibd)
+    where
+        D: SegmentedDisplay,
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let (blanks, text) = self.get_window(display);
+        let chars = core::iter::repeat_n(' ', blanks).chain(text.chars());
+
+        // the text usually continues beyond the display ('Truncated')
+        let mut buffer = [0b0000_0000; 16];
+        let _ = display.render_chars(chars, &mut buffer);
+
+        display.show_buffer(ibd, &buffer);
+    }
+}
+
+/// the byte offsets of the characters which start a new digit
+/// (same rules as layout_chars(): a dot is merged into the preceding digit,
+/// indicators do not use a digit)
+fn digit_starts<'t, F>(text: &'t str, is_indicator: F) -> impl Iterator<Item = usize> + 't
+where
+    F: Fn(char) -> bool + 't,
+{
+    let mut can_merge = false;
+
+    // implicit return
+    text.char_indices().filter_map(move |(offset, c)| {
+        if c == '.' && can_merge {
+            can_merge = false;
+            return None;
+        }
+        if is_indicator(c) {
+            can_merge = false;
+            return None;
+        }
+        can_merge = c != '.';
+        Some(offset)
+    })
+}
+
+#[cfg(test)]
+fn create_marquee(text: &str, width: usize, mode: ScrollMode, ticks_per_step: u32) -> Marquee<'_> {
+    // the colon is an indicator, like on the 7-segment backpack
+    let length = digit_starts(text, |c| c == ':').count();
+
+    Marquee {
+        text,
+        length: length as isize,
+        width: width as isize,
+        mode,
+        ticks_per_step,
+        ticks: 0,
+        position: 0,
+        is_moving_back: false,
+    }
+}
+
+#[cfg(test)]
+fn collect_windows<'a>(marquee: &mut Marquee<'a>, steps: usize) -> [(usize, &'a str); 8] {
+    let mut result = [(0, ""); 8];
+    for window in result.iter_mut().take(steps) {
+        *window = marquee.window(|c| c == ':');
+        marquee.step();
+    }
+
+    result
+}
+
+#[test]
+fn marquee_left() {
+    let mut marquee = create_marquee("ABCD", 3, ScrollMode::Left, 1);
+
+    let computed = collect_windows(&mut marquee, 8);
+    let expected = [
+        (0, "ABCD"),
+        (0, "BCD"),
+        (0, "CD"),
+        (0, "D"),
+        (3, ""),
+        (2, "ABCD"),
+        (1, "ABCD"),
+        (0, "ABCD"),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn marquee_right() {
+    let mut marquee = create_marquee("ABCD", 3, ScrollMode::Right, 1);
+
+    let computed = collect_windows(&mut marquee, 8);
+    let expected = [
+        (0, "ABCD"),
+        (1, "ABCD"),
+        (2, "ABCD"),
+        (3, ""),
+        (0, "D"),
+        (0, "CD"),
+        (0, "BCD"),
+        (0, "ABCD"),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn marquee_bounce() {
+    let mut marquee = create_marquee("ABCDE", 3, ScrollMode::Bounce, 1);
+
+    let computed = collect_windows(&mut marquee, 6);
+    let expected = [
+        (0, "ABCDE"),
+        (0, "BCDE"),
+        (0, "CDE"),
+        (0, "BCDE"),
+        (0, "ABCDE"),
+        (0, "BCDE"),
+        (0, ""),
+        (0, ""),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn marquee_bounce_short_text() {
+    let mut marquee = create_marquee("AB", 3, ScrollMode::Bounce, 1);
+
+    let computed = marquee.step();
+    let expected = false;
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn marquee_dots_and_indicators() {
+    // 6 digits: "1." "2" "3" "4" "." "5"
+    let mut marquee = create_marquee("1.2:34..5", 3, ScrollMode::Bounce, 1);
+
+    let computed = collect_windows(&mut marquee, 5);
+    let expected = [
+        (0, "1.2:34..5"),
+        (0, "2:34..5"),
+        (0, "34..5"),
+        (0, "4..5"),
+        (0, "34..5"),
+        (0, ""),
+        (0, ""),
+        (0, ""),
+    ];
+
+    assert_eq!(marquee.length, 6);
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn marquee_ticks_per_step() {
+    let mut marquee = create_marquee("ABCD", 3, ScrollMode::Left, 3);
+
+    let computed = [marquee.tick(), marquee.tick(), marquee.tick()];
+    let expected = [false, false, true];
+
+    assert_eq!(computed, expected);
+    assert_eq!(marquee.window(|c| c == ':'), (0, "BCD"));
+}
@@ -0,0 +1,580 @@
+/*
+    LED matrices (8×8, 16×8 and bicolor 8×8)
+
+    The drawing functions operate on an in-memory framebuffer. Nothing is
+    sent to the device until flush() is called.
+
+    The framebuffer uses the logical coordinates, (0, 0) is the top left
+    corner. Rotation and flipping are applied when a pixel is set, use them
+    to compensate for the way the matrix is wired to the backpack. Pixels
+    outside of the display are silently ignored.
+*/
+
+use crate::ht16k33::hw::Dimming;
+use crate::ht16k33::{BlinkRate, ConfigurationError, DeviceId, DisplayControl, LedDisplay};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// clockwise rotation of the displayed content
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rotation {
+    None,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+/// the colors supported by a bicolor matrix
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LedColor {
+    Off,
+    Green,
+    Red,
+    Yellow, // green + red
+}
+
+// ========================================================================
+// single color matrix
+// (row y is stored in bytes 2y (x = 0..7) and 2y+1 (x = 8..15))
+// ========================================================================
+
+/// single color LED matrix with 8 rows and WIDTH columns
+/// (WIDTH must be 8 or 16)
+pub struct Matrix<const WIDTH: usize> {
+    pub did: DeviceId,
+    pub rotation: Rotation,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    buffer: [u8; 16],
+    control: DisplayControl, // display mode, brightness level & what was sent to the device
+}
+
+pub type Matrix8x8 = Matrix<8>;
+pub type Matrix16x8 = Matrix<16>;
+
+impl<const WIDTH: usize> Matrix<WIDTH> {
+    /// create a new matrix (device ID must be in range 0 ≤ x ≤ 7)
+    /// (a WIDTH other than 8 or 16 fails to compile)
+    pub fn new(did: u8) -> Result<Self, ConfigurationError> {
+        const { assert!(WIDTH == 8 || WIDTH == 16, "WIDTH must be 8 or 16") };
+
+        let matrix = Matrix {
+            did: DeviceId::try_from(did)?,
+            rotation: Rotation::None,
+            flip_x: false,
+            flip_y: false,
+            buffer: [0x00; 16],
+            control: DisplayControl::new(BlinkRate::NoBlink, Dimming::Duty16),
+        };
+
+        // implicit return
+        Ok(matrix)
+    }
+
+    /// the raw framebuffer (as sent to the display)
+    pub fn get_buffer(&self) -> &[u8; 16] {
+        &self.buffer
+    }
+
+    /// the width after applying the rotation
+    pub fn get_width(&self) -> i32 {
+        // implicit return
+        match self.rotation {
+            Rotation::None | Rotation::Rotate180 => WIDTH as i32,
+            Rotation::Rotate90 | Rotation::Rotate270 => 8,
+        }
+    }
+
+    /// the height after applying the rotation
+    pub fn get_height(&self) -> i32 {
+        // implicit return
+        match self.rotation {
+            Rotation::None | Rotation::Rotate180 => 8,
+            Rotation::Rotate90 | Rotation::Rotate270 => WIDTH as i32,
+        }
+    }
+
+    /// turn off all pixels
+    pub fn clear(&mut self) {
+        self.buffer = [0x00; 16];
+    }
+
+    /// turn on all pixels
+    pub fn fill(&mut self) {
+        for y in 0..8 {
+            self.buffer[2 * y] = 0xFF;
+            if WIDTH > 8 {
+                self.buffer[2 * y + 1] = 0xFF;
+            }
+        }
+    }
+
+    pub fn set_pixel(&mut self, x: i32, y: i32) {
+        self.draw_pixel(x, y, true);
+    }
+
+    pub fn clear_pixel(&mut self, x: i32, y: i32) {
+        self.draw_pixel(x, y, false);
+    }
+
+    /// returns 'true' if the pixel is turned on
+    /// (pixels outside of the display are always turned off)
+    pub fn get_pixel(&self, x: i32, y: i32) -> bool {
+        match self.locate(x, y) {
+            Some((idx, mask)) => self.buffer[idx] & mask != 0,
+            None => false,
+        }
+    }
+
+    pub fn draw_pixel(&mut self, x: i32, y: i32, is_on: bool) {
+        if let Some((idx, mask)) = self.locate(x, y) {
+            if is_on {
+                self.buffer[idx] |= mask;
+            } else {
+                self.buffer[idx] &= !mask;
+            }
+        }
+    }
+
+    /// draw a line from (x0, y0) to (x1, y1), both ends included
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, is_on: bool) {
+        let bounds = (self.get_width(), self.get_height());
+        plot_line(x0, y0, x1, y1, bounds, |x, y| self.draw_pixel(x, y, is_on));
+    }
+
+    /// draw a rectangle with its top left corner at (x, y)
+    pub fn draw_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        filled: bool,
+        is_on: bool,
+    ) {
+        let bounds = (self.get_width(), self.get_height());
+        plot_rect(x, y, width, height, filled, bounds, |x, y| {
+            self.draw_pixel(x, y, is_on)
+        });
+    }
+
+    // find the byte and bit which represent the logical pixel
+    fn locate(&self, x: i32, y: i32) -> Option<(usize, u8)> {
+        let (px, py) = transform(
+            x,
+            y,
+            WIDTH as i32,
+            8,
+            self.rotation,
+            self.flip_x,
+            self.flip_y,
+        )?;
+
+        // implicit return
+        Some((2 * py + px / 8, 1 << (px % 8)))
+    }
+}
+
+impl<const WIDTH: usize> LedDisplay for Matrix<WIDTH> {
+    fn get_did(&self) -> DeviceId {
+        self.did
+    }
+
+    fn control(&self) -> &DisplayControl {
+        &self.control
+    }
+
+    fn control_mut(&mut self) -> &mut DisplayControl {
+        &mut self.control
+    }
+
+    fn get_framebuffer(&self) -> Option<&[u8; 16]> {
+        Some(&self.buffer)
+    }
+}
+
+// ========================================================================
+// bicolor matrix
+// (row y is stored in bytes 2y (green) and 2y+1 (red))
+// ========================================================================
+
+/// bicolor (red/green) LED matrix with 8 rows and 8 columns
+pub struct BicolorMatrix8x8 {
+    pub did: DeviceId,
+    pub rotation: Rotation,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    buffer: [u8; 16],
+    control: DisplayControl, // display mode, brightness level & what was sent to the device
+}
+
+impl BicolorMatrix8x8 {
+    /// create a new matrix (device ID must be in range 0 ≤ x ≤ 7)
+    pub fn new(did: u8) -> Result<Self, ConfigurationError> {
+        let matrix = BicolorMatrix8x8 {
+            did: DeviceId::try_from(did)?,
+            rotation: Rotation::None,
+            flip_x: false,
+            flip_y: false,
+            buffer: [0x00; 16],
+            control: DisplayControl::new(BlinkRate::NoBlink, Dimming::Duty16),
+        };
+
+        // implicit return
+        Ok(matrix)
+    }
+
+    /// the raw framebuffer (as sent to the display)
+    pub fn get_buffer(&self) -> &[u8; 16] {
+        &self.buffer
+    }
+
+    pub fn get_width(&self) -> i32 {
+        8
+    }
+
+    pub fn get_height(&self) -> i32 {
+        8
+    }
+
+    /// turn off all pixels
+    pub fn clear(&mut self) {
+        self.buffer = [0x00; 16];
+    }
+
+    /// set all pixels to the provided color
+    pub fn fill(&mut self, color: LedColor) {
+        let (green, red) = match color {
+            LedColor::Off => (0x00, 0x00),
+            LedColor::Green => (0xFF, 0x00),
+            LedColor::Red => (0x00, 0xFF),
+            LedColor::Yellow => (0xFF, 0xFF),
+        };
+        for y in 0..8 {
+            self.buffer[2 * y] = green;
+            self.buffer[2 * y + 1] = red;
+        }
+    }
+
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: LedColor) {
+        if let Some((idx, mask)) = self.locate(x, y) {
+            let (green, red) = match color {
+                LedColor::Off => (false, false),
+                LedColor::Green => (true, false),
+                LedColor::Red => (false, true),
+                LedColor::Yellow => (true, true),
+            };
+            set_bit(&mut self.buffer[idx], mask, green);
+            set_bit(&mut self.buffer[idx + 1], mask, red);
+        }
+    }
+
+    pub fn clear_pixel(&mut self, x: i32, y: i32) {
+        self.set_pixel(x, y, LedColor::Off);
+    }
+
+    /// returns the pixel's color
+    /// (pixels outside of the display are always turned off)
+    pub fn get_pixel(&self, x: i32, y: i32) -> LedColor {
+        let (idx, mask) = match self.locate(x, y) {
+            Some(location) => location,
+            None => return LedColor::Off,
+        };
+
+        let green = self.buffer[idx] & mask != 0;
+        let red = self.buffer[idx + 1] & mask != 0;
+
+        // implicit return
+        match (green, red) {
+            (false, false) => LedColor::Off,
+            (true, false) => LedColor::Green,
+            (false, true) => LedColor::Red,
+            (true, true) => LedColor::Yellow,
+        }
+    }
+
+    /// draw a line from (x0, y0) to (x1, y1), both ends included
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: LedColor) {
+        plot_line(x0, y0, x1, y1, (8, 8), |x, y| self.set_pixel(x, y, color));
+    }
+
+    /// draw a rectangle with its top left corner at (x, y)
+    pub fn draw_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        filled: bool,
+        color: LedColor,
+    ) {
+        plot_rect(x, y, width, height, filled, (8, 8), |x, y| {
+            self.set_pixel(x, y, color)
+        });
+    }
+
+    // find the green byte and bit which represent the logical pixel
+    // (the red byte follows the green byte)
+    fn locate(&self, x: i32, y: i32) -> Option<(usize, u8)> {
+        let (px, py) = transform(x, y, 8, 8, self.rotation, self.flip_x, self.flip_y)?;
+
+        // implicit return
+        Some((2 * py, 1 << px))
+    }
+}
+
+impl LedDisplay for BicolorMatrix8x8 {
+    fn get_did(&self) -> DeviceId {
+        self.did
+    }
+
+    fn control(&self) -> &DisplayControl {
+        &self.control
+    }
+
+    fn control_mut(&mut self) -> &mut DisplayControl {
+        &mut self.control
+    }
+
+    fn get_framebuffer(&self) -> Option<&[u8; 16]> {
+        Some(&self.buffer)
+    }
+}
+
+// ------------------------------------------------------------------------
+// helper functions
+// ------------------------------------------------------------------------
+
+fn set_bit(byte: &mut u8, mask: u8, is_set: bool) {
+    if is_set {
+        *byte |= mask;
+    } else {
+        *byte &= !mask;
+    }
+}
+
+/// convert logical coordinates into physical coordinates
+/// - returns 'None' if the pixel is outside of the display
+/// - the logical coordinates are flipped first and rotated afterwards
+fn transform(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    rotation: Rotation,
+    flip_x: bool,
+    flip_y: bool,
+) -> Option<(usize, usize)> {
+    let (lw, lh) = match rotation {
+        Rotation::None | Rotation::Rotate180 => (width, height),
+        Rotation::Rotate90 | Rotation::Rotate270 => (height, width),
+    };
+    if !(0..lw).contains(&x) || !(0..lh).contains(&y) {
+        return None;
+    }
+
+    let x = if flip_x { lw - 1 - x } else { x };
+    let y = if flip_y { lh - 1 - y } else { y };
+    let (px, py) = match rotation {
+        Rotation::None => (x, y),
+        Rotation::Rotate90 => (width - 1 - y, x),
+        Rotation::Rotate180 => (width - 1 - x, height - 1 - y),
+        Rotation::Rotate270 => (y, height - 1 - x),
+    };
+
+    // implicit return
+    Some((px as usize, py as usize))
+}
+
+/// plot a line, both ends included
+/// - only the pixels within the bounds (width, height) are visited, the
+///   end points may be anywhere
+/// - one pixel per step along the major axis, the minor coordinate is
+///   rounded to the nearest pixel (same result as Bresenham's algorithm)
+fn plot_line<F>(x0: i32, y0: i32, x1: i32, y1: i32, bounds: (i32, i32), mut plot: F)
+where
+    F: FnMut(i32, i32),
+{
+    // i64 avoids overflows for lines spanning the whole i32 range
+    let (x0, y0, x1, y1) = (x0 as i64, y0 as i64, x1 as i64, y1 as i64);
+    let (width, height) = (bounds.0 as i64, bounds.1 as i64);
+
+    if (x1 - x0).abs() >= (y1 - y0).abs() {
+        plot_along_major_axis((x0, y0), (x1, y1), (width, height), &mut plot);
+    } else {
+        plot_along_major_axis((y0, x0), (y1, x1), (height, width), |y, x| plot(x, y));
+    }
+}
+
+/// plot a line from (a0, b0) to (a1, b1) where 'a' is the major axis
+/// - only the part of the major axis within the bounds is iterated
+fn plot_along_major_axis<F>(start: (i64, i64), end: (i64, i64), bounds: (i64, i64), mut plot: F)
+where
+    F: FnMut(i32, i32),
+{
+    let ((a0, b0), (a1, b1)) = (start, end);
+    let step = if a0 <= a1 { 1 } else { -1 };
+
+    // clip the major axis to 0 ≤ a < bounds.0
+    let (first, last) = if step > 0 {
+        (a0.max(0), a1.min(bounds.0 - 1))
+    } else {
+        (a0.min(bounds.0 - 1), a1.max(0))
+    };
+    if (last - first) * step < 0 {
+        return;
+    }
+
+    let mut a = first;
+    loop {
+        let b = b0 + divide_rounded((a - a0) as i128 * (b1 - b0) as i128, (a1 - a0) as i128);
+        if (0..bounds.1).contains(&b) {
+            plot(a as i32, b as i32);
+        }
+        if a == last {
+            break;
+        }
+        a += step;
+    }
+}
+
+/// integer division, rounded to the nearest integer (ties round up)
+/// (returns 0 if the divisor is 0)
+fn divide_rounded(numerator: i128, denominator: i128) -> i64 {
+    if denominator == 0 {
+        return 0;
+    }
+    let (numerator, denominator) = if denominator < 0 {
+        (-numerator, -denominator)
+    } else {
+        (numerator, denominator)
+    };
+
+    // implicit return
+    (2 * numerator + denominator).div_euclid(2 * denominator) as i64
+}
+
+/// plot a rectangle (outline or filled)
+/// - only the pixels within the bounds (width, height) are visited
+fn plot_rect<F>(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    filled: bool,
+    bounds: (i32, i32),
+    mut plot: F,
+) where
+    F: FnMut(i32, i32),
+{
+    if width <= 0 || height <= 0 {
+        return;
+    }
+
+    // the last column and row ('None' if beyond the range of i32)
+    let x_last = x.checked_add(width - 1);
+    let y_last = y.checked_add(height - 1);
+
+    for py in y.max(0)..=y_last.unwrap_or(i32::MAX).min(bounds.1 - 1) {
+        for px in x.max(0)..=x_last.unwrap_or(i32::MAX).min(bounds.0 - 1) {
+            let is_edge = px == x || Some(px) == x_last || py == y || Some(py) == y_last;
+            if filled || is_edge {
+                plot(px, py);
+            }
+        }
+    }
+}
+
+#[test]
+fn transform_rotation() {
+    let computed = [
+        transform(0, 0, 16, 8, Rotation::None, false, false),
+        transform(0, 0, 16, 8, Rotation::Rotate90, false, false),
+        transform(0, 0, 16, 8, Rotation::Rotate180, false, false),
+        transform(0, 0, 16, 8, Rotation::Rotate270, false, false),
+    ];
+    let expected = [Some((0, 0)), Some((15, 0)), Some((15, 7)), Some((0, 7))];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn transform_outside() {
+    let computed = [
+        transform(-1, 0, 8, 8, Rotation::None, false, false),
+        transform(8, 0, 8, 8, Rotation::None, false, false),
+        transform(0, 8, 16, 8, Rotation::Rotate90, false, false),
+    ];
+    let expected = [None, None, Some((7, 0))];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn plot_line_diagonal() {
+    let mut computed = [(0, 0); 4];
+    let mut idx = 0;
+    plot_line(0, 0, 3, 3, (8, 8), |x, y| {
+        computed[idx] = (x, y);
+        idx += 1;
+    });
+    let expected = [(0, 0), (1, 1), (2, 2), (3, 3)];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn plot_line_shallow() {
+    let mut computed = [(0, 0); 6];
+    let mut idx = 0;
+    plot_line(5, 2, 0, 0, (8, 8), |x, y| {
+        computed[idx] = (x, y);
+        idx += 1;
+    });
+    let expected = [(5, 2), (4, 2), (3, 1), (2, 1), (1, 0), (0, 0)];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn plot_line_clipped() {
+    let mut count = 0;
+    let mut last = (0, 0);
+    plot_line(i32::MIN, 3, i32::MAX, 3, (8, 8), |x, y| {
+        count += 1;
+        last = (x, y);
+    });
+
+    assert_eq!((count, last), (8, (7, 3)));
+
+    count = 0;
+    plot_line(-100, -100, -1, -1, (8, 8), |_, _| count += 1);
+
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn plot_rect_clipped() {
+    let mut count = 0;
+    plot_rect(2, 2, i32::MAX, i32::MAX, false, (8, 8), |x, y| {
+        // only the left and top edge are visible
+        assert!(x == 2 || y == 2);
+        count += 1;
+    });
+
+    assert_eq!(count, 11);
+
+    count = 0;
+    plot_rect(
+        i32::MIN,
+        i32::MIN,
+        i32::MAX,
+        i32::MAX,
+        true,
+        (8, 8),
+        |_, _| count += 1,
+    );
+
+    assert_eq!(count, 0);
+}
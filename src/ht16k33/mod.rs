@@ -24,13 +24,52 @@
 
 use core::iter::Iterator;
 
+mod bargraph;
+mod brightness;
+mod cache;
+mod chain;
+mod clock;
 mod common;
 mod converter;
 pub mod did;
+mod display;
+mod effects;
+mod glyphs;
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
 pub mod hw;
-
-pub use common::{DisplayMode, SegmentedDisplay};
+mod keypad;
+mod layout;
+mod marquee;
+mod matrix;
+mod number_format;
+mod power;
+
+pub use bargraph::{BarGraph24, BarThresholds, BAR_COUNT};
+pub use brightness::{AdaptiveBrightness, LUX_CURVE};
+use cache::DisplayControl;
+pub use chain::DisplayChain;
+pub use clock::{Countdown, TimeFormat};
+pub use common::{ConfigurationError, SegmentedDisplay};
 pub use converter::{convert_14, convert_7, convert_to_4_digits};
+pub use did::{DeviceId, Package};
+pub use display::LedDisplay;
+pub use effects::{Animation, Effect, EffectStep};
+pub use glyphs::{GlyphTable, Glyphs14, Glyphs16, Glyphs7, LayeredGlyphs};
+use hw::Dimming;
+pub use hw::{BlinkRate, RowIntMode};
+pub use keypad::{convert_key_data, KeyEvent, KeyEvents, Keypad, KEY_COUNT};
+use layout::layout_chars;
+pub use layout::{
+    Fit, Wiring, DISPLAY_MAX_DIGITS, WIRING_14X4, WIRING_16X8, WIRING_7X4, WIRING_7X8,
+};
+pub use marquee::{Marquee, ScrollMode};
+pub use matrix::{BicolorMatrix8x8, LedColor, Matrix, Matrix16x8, Matrix8x8, Rotation};
+pub use number_format::{
+    Alignment, Decimals, FormattedNumber, NumberFormat, Overflow, Radix, NUMBER_MAX_DECIMALS,
+    NUMBER_MAX_DIGITS,
+};
+pub use power::{PowerManager, PowerState};
 
 #[allow(unused_imports)]
 use log::{debug, error, info, warn};
@@ -49,258 +88,184 @@ use log::{debug, error, info, warn};
 // }
 
 // ========================================================================
-// 7-segment, 4 digit display
-// (addressed as 5 digits: digit, digit, colon, digit, digit)
+// segmented displays
+// (one common line per digit, the wiring maps segments to rows)
 // ========================================================================
 
-pub struct Segment7x4 {
-    pub convert: fn(char) -> (u8, u8),
-    pub did: u8,
-    pub display_mode: DisplayMode,
-    pub brightness_level: u8,
-}
+/// 7-segment, 4 digit display
+/// (addressed as 5 digits: digit, digit, colon, digit, digit)
+pub type Segment7x4<G = Glyphs7> = SegmentDisplay<4, 8, G>;
 
-impl Segment7x4 {
-    fn configure_display<Ibd>(&self, ibd: &mut Ibd)
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        let da = did::convert_did_to_address(self.did);
-        // order is important
-        // make sure the oscillator is enabled or this becomes a no-op
-        hw::set_oscillator_mode(ibd, da, 1);
-        // configure display
-        hw::set_blink_rate(ibd, da, self.display_mode as u8);
-        hw::set_brightness_level(ibd, da, self.brightness_level);
-    }
-}
+/// 14-segment, 4 digit display
+/// (addressed as 4 digits: digit, digit, digit, digit)
+pub type Segment14x4<G = Glyphs14> = SegmentDisplay<4, 16, G>;
 
-impl SegmentedDisplay for Segment7x4 {
-    /// enter standby mode
-    /// - display will be turned off
-    /// - configuration is reset
-    /// - device must be woken up before doing anything
-    ///
-    /// This is the most energy-efficient mode. Use DisplayMode
-    /// 'Off' to disable the output but keep it running.
-    fn disable<Ibd>(&self, ibd: &mut Ibd)
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        let da = did::convert_did_to_address(self.did);
-        hw::set_oscillator_mode(ibd, da, 0);
-    }
+/// 7-segment, 8 digit display
+pub type Segment7x8<G = Glyphs7> = SegmentDisplay<8, 8, G>;
 
-    /// set the display's blink rate
-    /// (implicitly enables the display)
-    fn set_display_mode<Ibd>(&mut self, ibd: &mut Ibd, display_mode: DisplayMode)
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        // update internal value and apply configuration
-        self.display_mode = display_mode;
-        self.configure_display(ibd);
-    }
+/// 16-segment, 8 digit display
+pub type Segment16x8<G = Glyphs16> = SegmentDisplay<8, 16, G>;
 
-    /// set the display's brightness level
-    /// (implicitly enables the display)
-    /// - brightness level is graduated from 0 (6%) to 15 (100%)
-    /// - use the disable() function to turn off the display entirely
-    fn set_brightness_level<Ibd>(&mut self, ibd: &mut Ibd, brightness_level: u8) -> bool
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        const MAX: u8 = 15;
-        if brightness_level <= MAX {
-            // update internal value and apply configuration
-            self.brightness_level = brightness_level;
-            self.configure_display(ibd);
-            true
-        } else {
-            error!("Brightness level must be in range 0 ≤ x ≤ {MAX}");
-            false
+/// a display with DIGITS digits of SEGMENTS segments each
+/// - use the predefined types (e.g. Segment7x4) for the common layouts
+/// - use with_wiring() for boards with custom wiring
+pub struct SegmentDisplay<const DIGITS: usize, const SEGMENTS: usize, G> {
+    pub glyphs: G, // the character set
+    pub did: DeviceId,
+    wiring: &'static Wiring<DIGITS, SEGMENTS>,
+    control: DisplayControl, // display mode, brightness level & what was sent to the device
+}
+
+impl<const DIGITS: usize, const SEGMENTS: usize, G> SegmentDisplay<DIGITS, SEGMENTS, G>
+where
+    G: GlyphTable,
+{
+    /// create a new display with custom wiring
+    /// - device ID must be in range 0 ≤ x ≤ 7
+    /// - brightness level must be in range 0 ≤ x ≤ 15
+    /// - rows must be in range 0 ≤ x ≤ 15, common lines in range 0 ≤ x ≤ 7
+    /// - more than DISPLAY_MAX_DIGITS digits fail to compile
+    pub fn with_wiring(
+        glyphs: G,
+        wiring: &'static Wiring<DIGITS, SEGMENTS>,
+        did: u8,
+        display_mode: BlinkRate,
+        brightness_level: u8,
+    ) -> Result<Self, ConfigurationError> {
+        const { assert!(DIGITS <= DISPLAY_MAX_DIGITS, "too many digits") };
+
+        if let Some((row, com)) = wiring.find_invalid_bit() {
+            return Err(ConfigurationError::InvalidWiring(row, com));
         }
+        let display = SegmentDisplay {
+            glyphs,
+            did: DeviceId::try_from(did)?,
+            wiring,
+            control: DisplayControl::new(display_mode, Dimming::try_from(brightness_level)?),
+        };
+
+        // implicit return
+        Ok(display)
     }
 
-    /// display the provided data buffer
-    /// (implicitly enables the display)
-    /// - this is the most flexible way to address the display but the caller
-    ///   needs to know which bit controls which matrix dot / segment
-    fn show_buffer<Ibd>(&self, ibd: &mut Ibd, buffer: &[u8; 16])
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        self.configure_display(ibd);
-
-        // send data
-        let da = did::convert_did_to_address(self.did);
-        hw::set_display_data(ibd, da, buffer);
+    pub fn get_wiring(&self) -> &'static Wiring<DIGITS, SEGMENTS> {
+        self.wiring
     }
+}
 
-    fn show_number<Ibd>(&self, ibd: &mut Ibd, number: f32) -> bool
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        let mut buffer: [u8; 16] = convert_to_4_digits(number, self.convert);
-
-        // clear the third digit (colon)
-        buffer[9] = buffer[7];
-        buffer[8] = buffer[6];
-        buffer[7] = buffer[5];
-        buffer[6] = buffer[4];
-        buffer[5] = 0b0000_0000; // colon
-        buffer[4] = 0b0000_0000; // colon
-
-        self.show_buffer(ibd, &buffer);
-
-        true
+impl<G> Segment7x4<G>
+where
+    G: GlyphTable,
+{
+    /// create a new display (Adafruit 7-segment backpack)
+    /// - glyphs: e.g. Glyphs7::new() or convert_7
+    /// - device ID must be in range 0 ≤ x ≤ 7
+    /// - brightness level must be in range 0 ≤ x ≤ 15
+    pub fn new(
+        glyphs: G,
+        did: u8,
+        display_mode: BlinkRate,
+        brightness_level: u8,
+    ) -> Result<Self, ConfigurationError> {
+        // implicit return
+        SegmentDisplay::with_wiring(glyphs, &WIRING_7X4, did, display_mode, brightness_level)
     }
+}
 
-    fn show_string<Ibd>(&self, ibd: &mut Ibd, string: &str) -> bool
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        let mut buffer = [0b0000_0000; 16];
-
-        for (idx, c) in string.chars().enumerate() {
-            let offset1 = 2 * idx;
-            let offset2 = 2 * idx + 1;
-
-            let (byte1, byte2) = (self.convert)(c);
-
-            info!("offset byte1: {:02} -> {:#010b}", offset1, byte1);
-            info!("offset byte2: {:02} -> {:#010b}", offset2, byte2);
-            buffer[offset1] = byte1;
-            buffer[offset2] = byte2;
-        }
-
-        self.show_buffer(ibd, &buffer);
-
-        true
+impl<G> Segment14x4<G>
+where
+    G: GlyphTable,
+{
+    /// create a new display (Adafruit 14-segment backpack)
+    /// - glyphs: e.g. Glyphs14::new() or convert_14
+    /// - device ID must be in range 0 ≤ x ≤ 7
+    /// - brightness level must be in range 0 ≤ x ≤ 15
+    pub fn new(
+        glyphs: G,
+        did: u8,
+        display_mode: BlinkRate,
+        brightness_level: u8,
+    ) -> Result<Self, ConfigurationError> {
+        // implicit return
+        SegmentDisplay::with_wiring(glyphs, &WIRING_14X4, did, display_mode, brightness_level)
     }
 }
 
-// ========================================================================
-// 14-segment, 4 digit display
-// (addressed as 4 digits: digit, digit, digit, digit)
-// ========================================================================
-
-pub struct Segment14x4 {
-    pub convert: fn(char) -> (u8, u8),
-    pub did: u8,
-    pub display_mode: DisplayMode,
-    pub brightness_level: u8,
+impl<G> Segment7x8<G>
+where
+    G: GlyphTable,
+{
+    /// create a new display (digit n on common line n)
+    /// - glyphs: e.g. Glyphs7::new() or convert_7
+    /// - device ID must be in range 0 ≤ x ≤ 7
+    /// - brightness level must be in range 0 ≤ x ≤ 15
+    pub fn new(
+        glyphs: G,
+        did: u8,
+        display_mode: BlinkRate,
+        brightness_level: u8,
+    ) -> Result<Self, ConfigurationError> {
+        // implicit return
+        SegmentDisplay::with_wiring(glyphs, &WIRING_7X8, did, display_mode, brightness_level)
+    }
 }
 
-impl Segment14x4 {
-    fn configure_display<Ibd>(&self, ibd: &mut Ibd)
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        let da = did::convert_did_to_address(self.did);
-        // order is important
-        // make sure the oscillator is enabled or this becomes a no-op
-        hw::set_oscillator_mode(ibd, da, 1);
-        // configure display
-        hw::set_blink_rate(ibd, da, self.display_mode as u8);
-        hw::set_brightness_level(ibd, da, self.brightness_level);
+impl<G> Segment16x8<G>
+where
+    G: GlyphTable,
+{
+    /// create a new display (digit n on common line n)
+    /// - glyphs: e.g. Glyphs16::new()
+    /// - device ID must be in range 0 ≤ x ≤ 7
+    /// - brightness level must be in range 0 ≤ x ≤ 15
+    pub fn new(
+        glyphs: G,
+        did: u8,
+        display_mode: BlinkRate,
+        brightness_level: u8,
+    ) -> Result<Self, ConfigurationError> {
+        // implicit return
+        SegmentDisplay::with_wiring(glyphs, &WIRING_16X8, did, display_mode, brightness_level)
     }
 }
 
-impl SegmentedDisplay for Segment14x4 {
-    /// enter standby mode
-    /// - display will be turned off
-    /// - configuration is reset
-    /// - device must be woken up before doing anything
-    ///
-    /// This is the most energy-efficient mode. Use DisplayMode
-    /// 'Off' to disable the output but keep it running.
-    fn disable<Ibd>(&self, ibd: &mut Ibd)
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        let da = did::convert_did_to_address(self.did);
-        hw::set_oscillator_mode(ibd, da, 0);
+impl<const DIGITS: usize, const SEGMENTS: usize, G> LedDisplay
+    for SegmentDisplay<DIGITS, SEGMENTS, G>
+where
+    G: GlyphTable,
+{
+    fn get_did(&self) -> DeviceId {
+        self.did
     }
 
-    /// set the display's blink rate
-    /// (implicitly enables the display)
-    fn set_display_mode<Ibd>(&mut self, ibd: &mut Ibd, display_mode: DisplayMode)
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        // update internal value and apply configuration
-        self.display_mode = display_mode;
-        self.configure_display(ibd);
+    fn control(&self) -> &DisplayControl {
+        &self.control
     }
 
-    /// set the display's brightness level
-    /// (implicitly enables the display)
-    /// - brightness level is graduated from 0 (6%) to 15 (100%)
-    /// - use the disable() function to turn off the display entirely
-    fn set_brightness_level<Ibd>(&mut self, ibd: &mut Ibd, brightness_level: u8) -> bool
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        const MAX: u8 = 15;
-        if brightness_level <= MAX {
-            // update internal value and apply configuration
-            self.brightness_level = brightness_level;
-            self.configure_display(ibd);
-            true
-        } else {
-            error!("Brightness level must be in range 0 ≤ x ≤ {MAX}");
-            false
-        }
+    fn control_mut(&mut self) -> &mut DisplayControl {
+        &mut self.control
     }
+}
 
-    /// display the provided data buffer
-    /// (implicitly enables the display)
-    /// - this is the most flexible way to address the display but the caller
-    ///   needs to know which bit controls which matrix dot / segment
-    fn show_buffer<Ibd>(&self, ibd: &mut Ibd, buffer: &[u8; 16])
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        self.configure_display(ibd);
-
-        // send data
-        let da = did::convert_did_to_address(self.did);
-        hw::set_display_data(ibd, da, buffer);
+impl<const DIGITS: usize, const SEGMENTS: usize, G> SegmentedDisplay
+    for SegmentDisplay<DIGITS, SEGMENTS, G>
+where
+    G: GlyphTable,
+{
+    fn get_digit_count(&self) -> usize {
+        DIGITS
     }
 
-    fn show_number<Ibd>(&self, ibd: &mut Ibd, number: f32) -> bool
-    where
-        Ibd: crate::traits::I2cBusDevice,
-    {
-        let buffer: [u8; 16] = convert_to_4_digits(number, self.convert);
-
-        self.show_buffer(ibd, &buffer);
-
-        true
+    fn is_indicator(&self, c: char) -> bool {
+        self.wiring.get_indicator(c).is_some()
     }
 
-    fn show_string<Ibd>(&self, ibd: &mut Ibd, string: &str) -> bool
+    fn render_chars<I>(&self, chars: I, buffer: &mut [u8; 16]) -> Fit
     where
-        Ibd: crate::traits::I2cBusDevice,
+        I: Iterator<Item = char>,
     {
-        let mut buffer = [0b0000_0000; 16];
-
-        for (idx, c) in string.chars().enumerate() {
-            let offset1 = 2 * idx;
-            let offset2 = 2 * idx + 1;
-
-            let (byte1, byte2) = (self.convert)(c);
-
-            info!("offset byte1: {:02} -> {:#010b}", offset1, byte1);
-            info!("offset byte2: {:02} -> {:#010b}", offset2, byte2);
-            buffer[offset1] = byte1;
-            buffer[offset2] = byte2;
-        }
-
-        self.show_buffer(ibd, &buffer);
-
-        true
+        // implicit return
+        layout_chars(chars, self.wiring, &self.glyphs, buffer)
     }
 }
 
@@ -0,0 +1,388 @@
+/*
+    number formatting for segmented displays
+
+    The formatter produces characters which are suitable for
+    SegmentedDisplay::show_formatted(). The sign uses a digit, the dot does
+    not (it is merged into the preceding digit).
+
+    Rounding uses the exact binary value of the number and ties are
+    rounded away from zero (e.g. 0.125 -> "0.13", 2.5 -> "3"). Integer
+    inputs are formatted without any floating point operations.
+*/
+
+use crate::ht16k33::{Package, DISPLAY_MAX_DIGITS};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+/// the maximum number of digits supported by the formatter
+/// (the longest possible chain: 8 displays with 8 digits each)
+pub const NUMBER_MAX_DIGITS: usize =
+    Package::Sop28.get_device_count() as usize * DISPLAY_MAX_DIGITS;
+
+/// the maximum number of decimals
+/// (mantissa × 10^9 still fits into the integer arithmetic)
+pub const NUMBER_MAX_DECIMALS: u8 = 9;
+
+// each digit may be followed by a dot
+// (the number is ASCII, one byte per character)
+const NUMBER_MAX_BYTES: usize = 2 * NUMBER_MAX_DIGITS;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Decimals {
+    Fixed(u8), // always show exactly n decimals
+    Auto(u8),  // show as many decimals as possible (up to n)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alignment {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Radix {
+    Decimal,
+    Hexadecimal, // integers only, the decimals are ignored
+}
+
+/// what to display if the number does not fit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overflow {
+    Fill(char),                       // fill all digits with the glyph
+    Text(&'static str, &'static str), // the text for "too high" and "too low"
+}
+
+/// describes how numbers are displayed
+///
+/// usage:
+/// ```TEXT
+///
+/// let format = NumberFormat {
+///     decimals: Decimals::Fixed(1),
+///     ..NumberFormat::new(4)
+/// };
+/// sd.show_formatted(&mut ibd, &format.format_float(21.46));  // " 21.5"
+/// sd.show_formatted(&mut ibd, &format.format_integer(-7));   // " -7.0"
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NumberFormat {
+    pub digits: usize, // range: 1..=NUMBER_MAX_DIGITS
+    pub decimals: Decimals,
+    pub leading_zeros: bool, // pad with '0' instead of ' '
+    pub alignment: Alignment,
+    pub radix: Radix,
+    pub overflow: Overflow,
+}
+
+impl NumberFormat {
+    /// right-aligned decimal number with up to 3 decimals,
+    /// dashes are shown if the number does not fit
+    pub const fn new(digits: usize) -> Self {
+        NumberFormat {
+            digits,
+            decimals: Decimals::Auto(3),
+            leading_zeros: false,
+            alignment: Alignment::Right,
+            radix: Radix::Decimal,
+            overflow: Overflow::Fill('-'),
+        }
+    }
+
+    /// format the provided number
+    /// - 'Auto' reduces the number of decimals until the number fits
+    /// - NaN is treated as "too high"
+    pub fn format_float(&self, number: f32) -> FormattedNumber {
+        let is_negative = number.is_sign_negative() && !number.is_nan();
+        if !number.is_finite() {
+            return self.format_overflow(is_negative);
+        }
+
+        let (min_decimals, max_decimals) = match (self.radix, self.decimals) {
+            (Radix::Hexadecimal, _) => (0, 0),
+            (Radix::Decimal, Decimals::Fixed(n)) => {
+                let n = n.min(NUMBER_MAX_DECIMALS);
+                (n, n)
+            }
+            (Radix::Decimal, Decimals::Auto(n)) => (0, n.min(NUMBER_MAX_DECIMALS)),
+        };
+
+        for decimals in (min_decimals..=max_decimals).rev() {
+            let scaled = match scale_and_round(number, decimals) {
+                Some(scaled) => scaled,
+                None => continue,
+            };
+            let scale = 10u64.pow(decimals as u32);
+            // a number which rounds to zero has no sign
+            let result = self.format_parts(
+                is_negative && scaled != 0,
+                scaled / scale,
+                scaled % scale,
+                decimals,
+            );
+            if let Some(result) = result {
+                return result;
+            }
+        }
+
+        // implicit return
+        self.format_overflow(is_negative)
+    }
+
+    /// format the provided number (without using floating point operations)
+    /// - 'Fixed' appends the requested number of zeros
+    /// - 'Auto' does not use any decimals
+    pub fn format_integer(&self, number: i64) -> FormattedNumber {
+        let is_negative = number < 0;
+        let decimals = match (self.radix, self.decimals) {
+            (Radix::Decimal, Decimals::Fixed(n)) => n.min(NUMBER_MAX_DECIMALS),
+            _ => 0,
+        };
+
+        // implicit return
+        self.format_parts(is_negative, number.unsigned_abs(), 0, decimals)
+            .unwrap_or_else(|| self.format_overflow(is_negative))
+    }
+
+    fn get_digit_count(&self) -> usize {
+        self.digits.clamp(1, NUMBER_MAX_DIGITS)
+    }
+
+    // returns 'None' if the number does not fit
+    fn format_parts(
+        &self,
+        is_negative: bool,
+        integer: u64,
+        fraction: u64,
+        decimals: u8,
+    ) -> Option<FormattedNumber> {
+        let radix = match self.radix {
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16,
+        };
+
+        // collect the digits of the integer part (least significant first)
+        let mut integer_digits = [0u8; 20]; // u64::MAX has 20 decimal digits
+        let mut count = 0;
+        let mut value = integer;
+        loop {
+            integer_digits[count] = (value % radix) as u8;
+            count += 1;
+            value /= radix;
+            if value == 0 {
+                break;
+            }
+        }
+
+        let digits = self.get_digit_count();
+        let used = is_negative as usize + count + decimals as usize;
+        if used > digits {
+            return None;
+        }
+        let padding = digits - used;
+
+        let mut result = FormattedNumber::new();
+        if !self.leading_zeros && self.alignment == Alignment::Right {
+            result.push_repeated(' ', padding);
+        }
+        if is_negative {
+            result.push('-');
+        }
+        if self.leading_zeros {
+            result.push_repeated('0', padding);
+        }
+        for idx in (0..count).rev() {
+            result.push(convert_digit(integer_digits[idx]));
+        }
+        if decimals > 0 {
+            result.push('.');
+            for idx in (0..decimals as u32).rev() {
+                result.push(convert_digit((fraction / 10u64.pow(idx) % 10) as u8));
+            }
+        }
+        if !self.leading_zeros && self.alignment == Alignment::Left {
+            result.push_repeated(' ', padding);
+        }
+
+        // implicit return
+        Some(result)
+    }
+
+    fn format_overflow(&self, is_negative: bool) -> FormattedNumber {
+        let digits = self.get_digit_count();
+        let mut result = FormattedNumber::new();
+        result.is_overflow = true;
+
+        match self.overflow {
+            Overflow::Fill(glyph) => result.push_repeated(glyph, digits),
+            Overflow::Text(high, low) => {
+                let text = if is_negative { low } else { high };
+                let used = text.chars().take(digits).count();
+                if self.alignment == Alignment::Right {
+                    result.push_repeated(' ', digits - used);
+                }
+                for c in text.chars().take(digits) {
+                    result.push(c);
+                }
+                if self.alignment == Alignment::Left {
+                    result.push_repeated(' ', digits - used);
+                }
+            }
+        }
+
+        // implicit return
+        result
+    }
+}
+
+/// the output of NumberFormat, use chars() to display it
+/// - the characters are stored UTF-8 encoded (one byte per character of
+///   the number, the glyphs of Overflow may use more)
+/// - overflow glyphs which do not fit into the buffer are dropped (only
+///   possible for non-ASCII glyphs on more than 32 digits)
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormattedNumber {
+    bytes: [u8; NUMBER_MAX_BYTES],
+    length: usize,
+    is_overflow: bool,
+}
+
+impl FormattedNumber {
+    fn new() -> Self {
+        FormattedNumber {
+            bytes: [0x00; NUMBER_MAX_BYTES],
+            length: 0,
+            is_overflow: false,
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        let end = self.length + c.len_utf8();
+        if end > NUMBER_MAX_BYTES {
+            warn!("Formatted number is full, dropping '{c}'.");
+            return;
+        }
+        c.encode_utf8(&mut self.bytes[self.length..end]);
+        self.length = end;
+    }
+
+    fn push_repeated(&mut self, c: char, count: usize) {
+        for _ in 0..count {
+            self.push(c);
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // only complete characters are pushed, the bytes are valid UTF-8
+        core::str::from_utf8(&self.bytes[..self.length]).unwrap_or_default()
+    }
+
+    pub fn chars(&self) -> core::str::Chars<'_> {
+        self.as_str().chars()
+    }
+
+    /// the number did not fit, the characters contain the overflow glyphs
+    pub fn is_overflow(&self) -> bool {
+        self.is_overflow
+    }
+}
+
+impl core::fmt::Display for FormattedNumber {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        // implicit return
+        f.write_str(self.as_str())
+    }
+}
+
+// ------------------------------------------------------------------------
+// helper functions
+// ------------------------------------------------------------------------
+
+fn convert_digit(value: u8) -> char {
+    // implicit return
+    match value {
+        0..=9 => (b'0' + value) as char,
+        _ => (b'A' + value - 10) as char,
+    }
+}
+
+/// compute |number| × 10^decimals, rounded to the nearest integer
+/// - operates on the exact binary value (no intermediate rounding)
+/// - ties are rounded away from zero
+/// - returns 'None' if the result does not fit into u64
+fn scale_and_round(number: f32, decimals: u8) -> Option<u64> {
+    // f32: 1 bit sign, 8 bits exponent, 23 bits fraction
+    let bits = number.to_bits();
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let fraction = (bits & 0x007F_FFFF) as u128;
+    let (mantissa, exponent) = match exponent {
+        0 => (fraction, -149),                         // subnormal
+        _ => (fraction | 0x0080_0000, exponent - 150), // implicit leading 1
+    };
+
+    // the value is: mantissa × 10^decimals × 2^exponent (mantissa < 2^24)
+    let scaled = mantissa * 10u128.pow(decimals as u32);
+    if scaled == 0 {
+        return Some(0);
+    }
+    if exponent >= 0 {
+        // scaled < 2^54, the result exceeds u64 for larger shifts
+        if exponent >= 64 {
+            return None;
+        }
+        return u64::try_from(scaled << exponent).ok();
+    }
+
+    let shift = (-exponent) as u32;
+    if shift >= 64 {
+        // scaled < 2^54, less than half (rounds to zero)
+        return Some(0);
+    }
+    let quotient = scaled >> shift;
+    let remainder = scaled - (quotient << shift);
+    let half = 1u128 << (shift - 1);
+    let rounded = quotient + (remainder >= half) as u128;
+
+    // implicit return
+    u64::try_from(rounded).ok()
+}
+
+#[test]
+fn scale_and_round_pass() {
+    let computed = [
+        scale_and_round(0.0, 3),
+        scale_and_round(-0.5, 0),
+        scale_and_round(2.5, 0),
+        scale_and_round(0.125, 2),
+        scale_and_round(1.005, 2), // 1.00499999523...
+        scale_and_round(f32::MIN_POSITIVE, 9),
+        scale_and_round(16777216.0, 0),
+    ];
+    let expected = [
+        Some(0),
+        Some(1),
+        Some(3),
+        Some(13),
+        Some(100),
+        Some(0),
+        Some(16777216),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn scale_and_round_fail() {
+    let computed = scale_and_round(f32::MAX, 0);
+    let expected = None;
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn formatted_number_size() {
+    // one byte per character, each digit may be followed by a dot
+    let computed = core::mem::size_of::<FormattedNumber>();
+
+    assert!(computed <= 2 * NUMBER_MAX_DIGITS + 16);
+}
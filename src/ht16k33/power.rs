@@ -0,0 +1,137 @@
+/*
+    power management for HT16K33 displays
+
+    The HT16K33 knows three useful power states:
+
+    | state   | oscillator | display | key scanning | current  |
+    |:--------|:----------:|:-------:|:------------:|:--------:|
+    | Standby | off        | off     | no           | lowest   |
+    | Idle    | on         | off     | yes          | low      |
+    | Active  | on         | on      | yes          | normal   |
+
+    While a display is not 'Active' the show functions only remember the
+    frame, nothing is sent to the device. Brightness level, display mode
+    and the last frame are restored once the display is enabled again.
+
+    The power manager is driven by the caller (like the effects), the idle
+    timeout is expressed in ticks.
+*/
+
+use crate::ht16k33::{KeyEvent, KeyEvents, Keypad, LedDisplay};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerState {
+    Standby, // oscillator off (most energy-efficient)
+    Idle,    // oscillator on, display off (key scanning keeps running)
+    Active,  // oscillator on, display on
+}
+
+/// put the display to sleep after a number of ticks without activity
+///
+/// usage:
+/// ```TEXT
+///
+/// // sleep after 600 ticks (1 minute at 100ms per tick)
+/// let mut power = PowerManager::new(600);
+/// loop {
+///     // a key press wakes up the display and restarts the timeout
+///     for event in power.scan(&sd, &mut keypad, &mut ibd) {
+///         ...
+///     }
+///     ibd.sleep_ms(100);
+/// }
+/// ```
+pub struct PowerManager {
+    pub idle_ticks: u32,         // the timeout (0 = never sleep)
+    pub sleep_state: PowerState, // the state entered after the timeout
+    ticks: u32,                  // the number of ticks without activity
+}
+
+impl PowerManager {
+    /// create a power manager which puts the display into standby
+    pub fn new(idle_ticks: u32) -> Self {
+        PowerManager {
+            idle_ticks,
+            sleep_state: PowerState::Standby,
+            ticks: 0,
+        }
+    }
+
+    /// the number of ticks since the last activity
+    pub fn get_elapsed_ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// restart the timeout and wake up the display (if sleeping)
+    pub fn wake<D, Ibd>(&mut self, display: &D, ibd: &mut Ibd)
+    where
+        D: LedDisplay,
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.ticks = 0;
+        if display.get_power_state() != PowerState::Active {
+            display.enable(ibd);
+        }
+    }
+
+    /// advance by one tick, the display is put to sleep once the timeout
+    /// has expired
+    /// - returns the display's power state
+    pub fn tick<D, Ibd>(&mut self, display: &D, ibd: &mut Ibd) -> PowerState
+    where
+        D: LedDisplay,
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        self.advance(display, ibd, self.sleep_state);
+
+        // implicit return
+        display.get_power_state()
+    }
+
+    /// scan the keypad and advance by one tick
+    /// - a key press wakes up the display and restarts the timeout
+    /// - key scanning requires the oscillator, the display is therefore
+    ///   put into 'Idle' instead of 'Standby'
+    /// - the key events are passed on unchanged
+    pub fn scan<D, Ibd>(&mut self, display: &D, keypad: &mut Keypad, ibd: &mut Ibd) -> KeyEvents
+    where
+        D: LedDisplay,
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        let events = keypad.scan(ibd);
+        // iterate over a copy, the events are returned to the caller
+        let mut pending = events;
+        let is_pressed = pending.any(|event| matches!(event, KeyEvent::Pressed(_)));
+
+        if is_pressed {
+            self.wake(display, ibd);
+        } else {
+            self.advance(display, ibd, PowerState::Idle);
+        }
+
+        // implicit return
+        events
+    }
+
+    fn advance<D, Ibd>(&mut self, display: &D, ibd: &mut Ibd, sleep_state: PowerState)
+    where
+        D: LedDisplay,
+        Ibd: crate::traits::I2cBusDevice,
+    {
+        if self.idle_ticks == 0 || display.get_power_state() != PowerState::Active {
+            return;
+        }
+
+        self.ticks = self.ticks.saturating_add(1);
+        if self.ticks >= self.idle_ticks {
+            debug!(
+                "No activity for {0} ticks, entering {1:?}.",
+                self.ticks, sleep_state
+            );
+            display.set_power_state(ibd, sleep_state);
+        }
+    }
+}
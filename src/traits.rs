@@ -25,6 +25,14 @@ pub trait I2cBusDevice {
     /// write a single byte to device register 'dr'
     fn write_register_as_byte(&mut self, da: u8, dr: u8, byte: u8);
 
+    /// read a 16 bit device register 'dr'
+    /// (a single write-read transaction: the register pointer is written,
+    /// then two bytes are read)
+    ///
+    /// returns the bytes in the order received, the byte order of the
+    /// value depends on the device
+    fn read_register_as_word(&mut self, da: u8, dr: u8) -> [u8; 2];
+
     /// read multiple independent registers in the exact order provided
     ///
     /// returns the register's values in exactly the same order
@@ -33,6 +41,32 @@ pub trait I2cBusDevice {
     /// write two independent registers in the exact order provided
     fn write_multibyte_register_as_u8<const N: usize>(&mut self, da: u8, values: [[u8; 2]; N]);
 
+    /// read consecutive registers, starting with device register 'dr'
+    /// (the device's address pointer auto-increments after each byte)
+    ///
+    /// fills the provided buffer with the register's values
+    ///
+    /// The default implementation falls back to reading each register
+    /// individually. Implementers should override this function with a
+    /// single write-read transaction to reduce the bus time.
+    fn read_register_block(&mut self, da: u8, dr: u8, buffer: &mut [u8]) {
+        for (offset, value) in buffer.iter_mut().enumerate() {
+            *value = self.read_register_as_byte(da, dr.wrapping_add(offset as u8));
+        }
+    }
+
+    /// write consecutive registers, starting with device register 'dr'
+    /// (the device's address pointer auto-increments after each byte)
+    ///
+    /// The default implementation falls back to writing each register
+    /// individually. Implementers should override this function with a
+    /// single write transaction to reduce the bus time.
+    fn write_register_block(&mut self, da: u8, dr: u8, bytes: &[u8]) {
+        for (offset, value) in bytes.iter().enumerate() {
+            self.write_register_as_byte(da, dr.wrapping_add(offset as u8), *value);
+        }
+    }
+
     // some functions require a little time to pass
     // the sleep function is hardware-dependent and must be provided by
     // the caller
@@ -0,0 +1,129 @@
+/*
+    a virtual ADS1x1x (used for testing without hardware)
+*/
+
+pub struct VirtualAds1x1x {
+    pub resolution: u8,          // 12 or 16 bit
+    pub inputs: [i16; 8],        // the result per MUX setting (in counts)
+    pub conversion: u16,         // conversion register (0x00)
+    pub config: u16,             // config register (0x01)
+    pub lo_thresh: u16,          // Lo_thresh register (0x02)
+    pub hi_thresh: u16,          // Hi_thresh register (0x03)
+    pub busy_polls: Option<u32>, // polls until a conversion finishes (None = never)
+    pub remaining: Option<u32>,  // polls until the running conversion finishes
+    pub slept_ms: u32,           // total time slept
+    pub wrc: usize,              // number of write transactions
+}
+
+impl VirtualAds1x1x {
+    // store the result of the selected input, left-aligned
+    fn convert(&mut self) {
+        let mux = (self.config >> 12) & 0b111;
+        let shift = 16 - self.resolution;
+        self.conversion = (self.inputs[mux as usize] << shift) as u16;
+    }
+}
+
+impl i2c_devices::I2cBusDevice for VirtualAds1x1x {
+    fn read_byte(&mut self, da: u8) -> Result<u8, &'static str> {
+        validate_device_address(da);
+
+        panic!("function not implemented")
+    }
+
+    fn write_byte(&mut self, da: u8, _byte: u8) {
+        validate_device_address(da);
+
+        panic!("function not implemented")
+    }
+
+    fn write_bytes(&mut self, da: u8, bytes: &[u8]) {
+        validate_device_address(da);
+        self.wrc += 1;
+
+        // the address pointer followed by MSB and LSB
+        if bytes.len() != 3 {
+            panic!("invalid write")
+        }
+        let value = u16::from_be_bytes([bytes[1], bytes[2]]);
+        match bytes[0] {
+            0x01 => {
+                // writing the OS bit starts a single conversion,
+                // the bit reads 0 until the conversion has finished
+                if value & 0x8000 != 0 {
+                    self.config = value & 0x7FFF;
+                    self.remaining = self.busy_polls;
+                } else {
+                    self.config = value | 0x8000;
+                }
+            }
+            0x02 => self.lo_thresh = value,
+            0x03 => self.hi_thresh = value,
+            _ => panic!("attempted write to read-only register {:#04X}", bytes[0]),
+        }
+    }
+
+    fn read_register_as_byte(&mut self, da: u8, _dr: u8) -> u8 {
+        validate_device_address(da);
+
+        panic!("function not implemented")
+    }
+
+    fn write_register_as_byte(&mut self, da: u8, _dr: u8, _byte: u8) {
+        validate_device_address(da);
+
+        panic!("function not implemented")
+    }
+
+    fn read_multibyte_register_as_u8<const N: usize>(&mut self, da: u8, _dr: [u8; N]) -> [u8; N] {
+        validate_device_address(da);
+
+        panic!("function not implemented")
+    }
+
+    fn write_multibyte_register_as_u8<const N: usize>(&mut self, da: u8, _values: [[u8; 2]; N]) {
+        validate_device_address(da);
+
+        panic!("function not implemented")
+    }
+
+    fn read_register_as_word(&mut self, da: u8, dr: u8) -> [u8; 2] {
+        validate_device_address(da);
+
+        let value = match dr {
+            0x00 => {
+                // continuous mode (MODE = 0): always the latest result
+                if self.config & 0x0100 == 0 {
+                    self.convert();
+                }
+                self.conversion
+            }
+            0x01 => {
+                match self.remaining {
+                    Some(0) => {
+                        self.convert();
+                        self.remaining = None;
+                        self.config |= 0x8000;
+                    }
+                    Some(polls) => self.remaining = Some(polls - 1),
+                    None => {}
+                }
+                self.config
+            }
+            0x02 => self.lo_thresh,
+            0x03 => self.hi_thresh,
+            _ => panic!("invalid register {dr:#04X}"),
+        };
+        value.to_be_bytes()
+    }
+
+    fn sleep_ms(&mut self, milliseconds: u32) {
+        self.slept_ms += milliseconds;
+    }
+}
+
+fn validate_device_address(da: u8) {
+    if !(0x48..=0x4B).contains(&da) {
+        panic!("device address must be in range 0x48 ≤ x ≤ 0x4B")
+    }
+}
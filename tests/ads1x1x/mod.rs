@@ -0,0 +1,271 @@
+/*
+    high-level integration tests for ADS1x1x
+    (using 'i2c_devices::ads1x1x')
+*/
+
+mod common;
+
+use common::VirtualAds1x1x;
+
+use i2c_devices::ads1x1x as sut;
+
+// ------------------------------------------------------------------------
+
+#[test]
+fn read_raw_single_ended() {
+    let mut vbd = create_ads1x1x(16);
+    vbd.inputs = [0, 0, 0, 0, 100, -200, 3000, 32767];
+
+    let adc = sut::Ads1x1x::<sut::Ads1115>::new(sut::AddressPin::Gnd);
+    let computed = [
+        adc.read_raw(&mut vbd, sut::Channel::Ain0),
+        adc.read_raw(&mut vbd, sut::Channel::Ain1),
+        adc.read_raw(&mut vbd, sut::Channel::Ain2),
+        adc.read_raw(&mut vbd, sut::Channel::Ain3),
+    ];
+    let expected = [Ok(100), Ok(-200), Ok(3000), Ok(32767)];
+
+    assert_eq!(computed, expected);
+    // the device powers down after each conversion
+    assert_eq!(vbd.config & 0x0100, 0x0100);
+}
+
+#[test]
+fn read_raw_differential() {
+    let mut vbd = create_ads1x1x(12);
+    vbd.inputs = [-2048, 1, 2, 2047, 0, 0, 0, 0];
+
+    let adc = sut::Ads1x1x::<sut::Ads1015>::new(sut::AddressPin::Scl);
+    let computed = [
+        adc.read_raw(&mut vbd, sut::Channel::Diff01),
+        adc.read_raw(&mut vbd, sut::Channel::Diff03),
+        adc.read_raw(&mut vbd, sut::Channel::Diff13),
+        adc.read_raw(&mut vbd, sut::Channel::Diff23),
+    ];
+    let expected = [Ok(-2048), Ok(1), Ok(2), Ok(2047)];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn read_raw_fixed_input() {
+    let mut vbd = create_ads1x1x(16);
+    vbd.inputs = [-1234, 0, 0, 0, 4321, 0, 0, 0];
+
+    // no MUX, the input is always AIN0 - AIN1
+    let adc = sut::Ads1x1x::<sut::Ads1114>::new(sut::AddressPin::Gnd);
+    let computed = adc.read_raw(&mut vbd, sut::FixedInput::Diff01);
+    let expected = Ok(-1234);
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.config & 0x7000, 0x0000);
+}
+
+#[test]
+fn read_raw_polls_until_finished() {
+    let mut vbd = create_ads1x1x(16);
+    vbd.inputs[4] = 1234;
+    vbd.busy_polls = Some(5);
+
+    let adc = sut::Ads1x1x::<sut::Ads1115>::new(sut::AddressPin::Vdd);
+    let computed = adc.read_raw(&mut vbd, sut::Channel::Ain0);
+    let expected = Ok(1234);
+
+    assert_eq!(computed, expected);
+    // 9ms conversion time (128 SPS) + 5 additional polls
+    assert_eq!(vbd.slept_ms, 14);
+}
+
+#[test]
+fn read_raw_timeout() {
+    let mut vbd = create_ads1x1x(16);
+    vbd.busy_polls = None;
+
+    let adc = sut::Ads1x1x::<sut::Ads1115>::new(sut::AddressPin::Sda);
+    let computed = adc.read_raw(&mut vbd, sut::Channel::Ain0);
+    let expected = Err(sut::Error::Timeout);
+
+    assert_eq!(computed, expected);
+    // 9ms conversion time (128 SPS) + 10 additional polls
+    assert_eq!(vbd.slept_ms, 19);
+}
+
+#[test]
+fn read_raw_conversion_time() {
+    let mut vbd = create_ads1x1x(12);
+
+    let mut adc = sut::Ads1x1x::<sut::Ads1015>::new(sut::AddressPin::Gnd);
+    let mut computed = Vec::new();
+    for data_rate in [
+        sut::DataRate::Sps128,
+        sut::DataRate::Sps920,
+        sut::DataRate::Sps3300,
+    ] {
+        adc.set_data_rate(data_rate).unwrap();
+        vbd.slept_ms = 0;
+        adc.read_raw(&mut vbd, sut::Channel::Ain0).unwrap();
+        computed.push((vbd.slept_ms, (vbd.config >> 5) & 0b111));
+    }
+    let expected = [(9, 0b000), (2, 0b011), (1, 0b110)];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn with_data_rate() {
+    let computed = [
+        sut::Ads1x1x::<sut::Ads1115>::with_data_rate(sut::AddressPin::Gnd, sut::DataRate::Sps860)
+            .map(|adc| adc.get_data_rate()),
+        sut::Ads1x1x::<sut::Ads1115>::with_data_rate(sut::AddressPin::Gnd, sut::DataRate::Sps3300)
+            .map(|adc| adc.get_data_rate()),
+        sut::Ads1x1x::<sut::Ads1014>::with_data_rate(sut::AddressPin::Gnd, sut::DataRate::Sps8)
+            .map(|adc| adc.get_data_rate()),
+    ];
+    let expected = [
+        Ok(sut::DataRate::Sps860),
+        Err(sut::Error::UnsupportedDataRate(sut::DataRate::Sps3300)),
+        Err(sut::Error::UnsupportedDataRate(sut::DataRate::Sps8)),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn set_gain() {
+    let mut vbd = create_ads1x1x(16);
+    vbd.inputs[4] = 16000;
+
+    let mut adc = sut::Ads1x1x::<sut::Ads1115>::new(sut::AddressPin::Gnd);
+    let mut computed = Vec::new();
+    for gain in [sut::Gain::Fsr6144, sut::Gain::Fsr2048, sut::Gain::Fsr256] {
+        adc.set_gain(gain);
+        let mv = adc.read_millivolts(&mut vbd, sut::Channel::Ain0);
+        computed.push((mv, sut::Gain::from(((vbd.config >> 9) & 0b111) as u8)));
+    }
+    let expected = [
+        (Ok(3000.0), sut::Gain::Fsr6144),
+        (Ok(1000.0), sut::Gain::Fsr2048),
+        (Ok(125.0), sut::Gain::Fsr256),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn read_millivolts() {
+    let mut vbd12 = create_ads1x1x(12);
+    vbd12.inputs[0] = 1500;
+    let mut vbd16 = create_ads1x1x(16);
+    vbd16.inputs[0] = -8000;
+
+    let ads1013 = sut::Ads1x1x::<sut::Ads1013>::new(sut::AddressPin::Gnd);
+    let ads1113 = sut::Ads1x1x::<sut::Ads1113>::new(sut::AddressPin::Gnd);
+    let computed = [
+        ads1013.read_millivolts(&mut vbd12, sut::FixedInput::Diff01),
+        ads1113.read_millivolts(&mut vbd16, sut::FixedInput::Diff01),
+    ];
+    let expected = [Ok(1500.0), Ok(-500.0)];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn start_continuous() {
+    let mut vbd = create_ads1x1x(16);
+
+    let adc =
+        sut::Ads1x1x::<sut::Ads1115>::with_data_rate(sut::AddressPin::Gnd, sut::DataRate::Sps860)
+            .unwrap();
+    adc.start_continuous(&mut vbd, sut::Channel::Ain1);
+
+    // conversion-ready mode
+    assert_eq!((vbd.hi_thresh, vbd.lo_thresh), (0x8000, 0x0000));
+    // continuous mode, 860 SPS, ALERT/RDY after one conversion
+    assert_eq!(vbd.config & 0x7FFF, 0x54E0);
+}
+
+#[test]
+fn start_continuous_without_comparator() {
+    let mut vbd = create_ads1x1x(12);
+
+    let adc = sut::Ads1x1x::<sut::Ads1013>::new(sut::AddressPin::Gnd);
+    adc.start_continuous(&mut vbd, sut::FixedInput::Diff01);
+
+    // the thresholds are not touched, the comparator stays disabled
+    assert_eq!((vbd.hi_thresh, vbd.lo_thresh), (0x7FFF, 0x8000));
+    assert_eq!(vbd.config & 0x0103, 0x0003);
+    assert_eq!(vbd.wrc, 1);
+}
+
+#[test]
+fn read_latest() {
+    let mut vbd = create_ads1x1x(16);
+
+    let adc = sut::Ads1x1x::<sut::Ads1115>::new(sut::AddressPin::Gnd);
+    adc.start_continuous(&mut vbd, sut::Channel::Ain3);
+    let wrc = vbd.wrc;
+    let mut computed = Vec::new();
+    for input in [100, -100, 32767] {
+        vbd.inputs[7] = input;
+        computed.push(adc.read_latest(&mut vbd));
+    }
+    let expected = [100, -100, 32767];
+
+    assert_eq!(computed, expected);
+    // no conversion is triggered
+    assert_eq!(vbd.wrc, wrc);
+    assert_eq!(vbd.slept_ms, 0);
+}
+
+#[test]
+fn read_latest_without_continuous() {
+    let mut vbd = create_ads1x1x(16);
+    vbd.inputs[4] = 1234;
+
+    let adc = sut::Ads1x1x::<sut::Ads1115>::new(sut::AddressPin::Gnd);
+    let mut computed = vec![adc.read_latest(&mut vbd)];
+    adc.read_raw(&mut vbd, sut::Channel::Ain0).unwrap();
+    vbd.inputs[4] = 4321;
+    computed.push(adc.read_latest(&mut vbd));
+    // power-up value, then the stale single-shot result
+    let expected = [0, 1234];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn power_down() {
+    let mut vbd = create_ads1x1x(16);
+    vbd.inputs[4] = 1234;
+
+    let adc = sut::Ads1x1x::<sut::Ads1115>::new(sut::AddressPin::Gnd);
+    adc.start_continuous(&mut vbd, sut::Channel::Ain2);
+    adc.power_down(&mut vbd);
+
+    // single-shot mode, comparator and thresholds reset
+    assert_eq!(vbd.config, 0xE583);
+    assert_eq!((vbd.hi_thresh, vbd.lo_thresh), (0x7FFF, 0x8000));
+
+    // single-shot conversions work as before
+    let computed = adc.read_raw(&mut vbd, sut::Channel::Ain0);
+    let expected = Ok(1234);
+
+    assert_eq!(computed, expected);
+}
+
+// ------------------------------------------------------------------------
+
+fn create_ads1x1x(resolution: u8) -> VirtualAds1x1x {
+    VirtualAds1x1x {
+        resolution,
+        inputs: [0; 8],
+        conversion: 0x0000,
+        config: 0x8583,
+        lo_thresh: 0x8000,
+        hi_thresh: 0x7FFF,
+        busy_polls: Some(0),
+        remaining: None,
+        slept_ms: 0,
+        wrc: 0,
+    }
+}
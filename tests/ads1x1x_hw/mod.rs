@@ -0,0 +1,87 @@
+/*
+    low-level integration tests for ADS1x1x
+    (using 'i2c_devices::ads1x1x::hw')
+*/
+
+mod common;
+
+use common::VirtualAds1x1x;
+
+use i2c_devices::ads1x1x::hw as sut;
+
+// ------------------------------------------------------------------------
+
+#[test]
+fn get_config() {
+    let mut vbd = create_ads1x1x();
+
+    let computed = sut::get_config(&mut vbd, 0x48);
+    let expected = sut::CONFIG_DEFAULT;
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn set_config() {
+    let mut vbd = create_ads1x1x();
+
+    sut::set_config(&mut vbd, 0x49, 0x0483);
+
+    assert_eq!(vbd.config, 0x8483);
+    assert_eq!(vbd.wrc, 1);
+}
+
+#[test]
+fn get_conversion() {
+    let mut vbd = create_ads1x1x();
+    vbd.conversion = 0xABCD;
+
+    let computed = sut::get_conversion(&mut vbd, 0x4A);
+    let expected = 0xABCD;
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn set_thresholds() {
+    let mut vbd = create_ads1x1x();
+
+    sut::set_lo_thresh(&mut vbd, 0x4B, 0x1234);
+    sut::set_hi_thresh(&mut vbd, 0x4B, 0x5678);
+
+    let computed = [
+        sut::get_lo_thresh(&mut vbd, 0x4B),
+        sut::get_hi_thresh(&mut vbd, 0x4B),
+    ];
+    let expected = [0x1234, 0x5678];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn write_register_read_only() {
+    let mut vbd = create_ads1x1x();
+
+    let computed = sut::write_register(&mut vbd, 0x48, sut::Register::Conversion, 0x1234);
+    let expected = false;
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.wrc, 0);
+}
+
+// ------------------------------------------------------------------------
+
+fn create_ads1x1x() -> VirtualAds1x1x {
+    VirtualAds1x1x {
+        resolution: 16,
+        inputs: [0; 8],
+        conversion: 0x0000,
+        config: 0x8583,
+        lo_thresh: 0x8000,
+        hi_thresh: 0x7FFF,
+        busy_polls: Some(0),
+        remaining: None,
+        slept_ms: 0,
+        wrc: 0,
+    }
+}
@@ -34,6 +34,13 @@ impl i2c_devices::I2cBusDevice for VirtualI2cBusDevice {
         self.registers[dr as usize].0
     }
 
+    fn read_register_as_word(&mut self, da: u8, _dr: u8) -> [u8; 2] {
+        validate_device_address(da);
+
+        panic!("function not implemented")
+    }
+
+    #[allow(clippy::unused_format_specs)]
     fn write_register_as_byte(&mut self, da: u8, dr: u8, byte: u8) {
         validate_device_address(da);
 
@@ -57,6 +64,7 @@ impl i2c_devices::I2cBusDevice for VirtualI2cBusDevice {
         rb
     }
 
+    #[allow(clippy::unused_format_specs)]
     fn write_multibyte_register_as_u8<const N: usize>(&mut self, da: u8, values: [[u8; 2]; N]) {
         validate_device_address(da);
 
@@ -72,11 +80,34 @@ impl i2c_devices::I2cBusDevice for VirtualI2cBusDevice {
         }
     }
 
+    fn read_register_block(&mut self, da: u8, dr: u8, buffer: &mut [u8]) {
+        validate_device_address(da);
+
+        // the address pointer is incremented after each byte
+        for (offset, value) in buffer.iter_mut().enumerate() {
+            *value = self.registers[dr as usize + offset].0;
+        }
+    }
+
+    fn write_register_block(&mut self, da: u8, dr: u8, bytes: &[u8]) {
+        validate_device_address(da);
+
+        // the address pointer is incremented after each byte
+        for (offset, value) in bytes.iter().enumerate() {
+            let register = dr as usize + offset;
+            if self.registers[register].1 {
+                self.registers[register].0 = *value;
+            } else {
+                panic!("attempted write to read-only register {register:#04X}")
+            }
+        }
+    }
+
     // some hardware functions require a little time to pass
     // - functions that sleep mention this fact in their documentation
     // - sleeping is hardware-dependent, no_std provides no abstraction
     fn sleep_ms(&mut self, _milliseconds: u32) {
-        panic!("function not implemented")
+        // no need to wait, the virtual device responds immediately
     }
 }
 
@@ -73,6 +73,7 @@ fn get_config_register() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn set_config_register() {
     let mut vbd = create_emc2101();
     let cr = sut::ConfigRegister {
@@ -386,9 +387,10 @@ fn get_hardware_details_emc2101() {
         pid: 0x16,
         product: "EMC2101",
         revision: 1,
+        variant: sut::Variant::Emc2101,
     };
 
-    assert_eq!(computed, expected);
+    assert_eq!(computed, Ok(expected));
 }
 
 #[test]
@@ -402,25 +404,125 @@ fn get_hardware_details_emc2101r() {
         pid: 0x28,
         product: "EMC2101-R",
         revision: 1,
+        variant: sut::Variant::Emc2101R,
     };
 
+    assert_eq!(computed, Ok(expected));
+}
+
+#[test]
+fn get_hardware_details_unknown_manufacturer() {
+    let mut vbd = create_emc2101();
+    vbd.registers[0xFE] = (0x5E, false);
+
+    let computed = sut::get_hardware_details(&mut vbd);
+    let expected = Err(sut::HardwareError::UnknownManufacturer(0x5E));
+
     assert_eq!(computed, expected);
 }
 
 #[test]
-fn get_hardware_details_unknown() {
+fn get_hardware_details_unknown_product() {
     let mut vbd = create_emc2101();
     vbd.registers[0xFD] = (0x17, false);
-    vbd.registers[0xFE] = (0x5E, false);
 
     let computed = sut::get_hardware_details(&mut vbd);
-    let expected = sut::HardwareDetails {
-        mid: 0x5E,
-        manufacturer: "<unknown>",
-        pid: 0x17,
-        product: "<unknown>",
-        revision: 1,
-    };
+    let expected = Err(sut::HardwareError::UnknownProduct(0x17));
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn get_variant_emc2101() {
+    let mut vbd = create_emc2101();
+
+    let computed = sut::get_variant(&mut vbd);
+    let expected = Ok(sut::Variant::Emc2101);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn get_variant_emc2101r() {
+    let mut vbd = create_emc2101r();
+
+    let computed = sut::get_variant(&mut vbd);
+    let expected = Ok(sut::Variant::Emc2101R);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn get_eeprom_status_emc2101() {
+    let mut vbd = create_emc2101();
+
+    let computed = sut::get_eeprom_status(&mut vbd);
+    let expected = Err(sut::HardwareError::UnsupportedVariant(
+        sut::Variant::Emc2101,
+    ));
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn get_eeprom_status_loaded() {
+    let mut vbd = create_emc2101r();
+
+    let computed = sut::get_eeprom_status(&mut vbd);
+    let expected = Ok(sut::EepromStatus::Loaded);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn get_eeprom_status_not_found() {
+    let mut vbd = create_emc2101r();
+    vbd.registers[0x02] = (0b0010_0000, false);
+
+    let computed = sut::get_eeprom_status(&mut vbd);
+    let expected = Ok(sut::EepromStatus::NotFound);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn power_up_poll() {
+    let mut vbd = create_emc2101r();
+    vbd.registers[0x02] = (0b1000_0000, false);
+
+    let mut power_up = sut::PowerUp::new(10);
+    let mut computed = vec![power_up.poll(&mut vbd)];
+    vbd.registers[0x02] = (0b0000_0000, false);
+    computed.push(power_up.poll(&mut vbd));
+    let expected = [Ok(None), Ok(Some(sut::Variant::Emc2101R))];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn power_up_poll_emc2101() {
+    let mut vbd = create_emc2101();
+    vbd.registers[0x02] = (0b1000_0000, false);
+
+    let mut power_up = sut::PowerUp::new(10);
+    let computed = power_up.poll(&mut vbd);
+    let expected = Ok(Some(sut::Variant::Emc2101));
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn power_up_poll_timeout() {
+    let mut vbd = create_emc2101r();
+    vbd.registers[0x02] = (0b1000_0000, false);
+
+    let mut power_up = sut::PowerUp::new(3);
+    let computed = [
+        power_up.poll(&mut vbd),
+        power_up.poll(&mut vbd),
+        power_up.poll(&mut vbd),
+    ];
+    let expected = [Ok(None), Ok(None), Err(sut::HardwareError::Timeout)];
 
     assert_eq!(computed, expected);
 }
@@ -717,6 +819,56 @@ fn get_rpm() {
     assert_eq!(computed, expected);
 }
 
+#[test]
+fn dump_registers() {
+    let mut vbd = create_emc2101();
+    vbd.registers[0x00].0 = 0x1A; // internal temperature: 26°C
+
+    let computed = sut::dump_registers(&mut vbd);
+
+    assert_eq!(computed.get(0x00), Some(0x1A));
+    assert_eq!(computed.get(0x16), Some(0xA4));
+    assert_eq!(computed.get(0x5F), Some(0x3F));
+    assert_eq!(computed.get(0xFD), Some(0x16));
+    assert_eq!(computed.get(0x09), None); // alias register (not listed)
+    assert_eq!(computed.iter().count(), 50);
+}
+
+#[test]
+fn dump_registers_display() {
+    let mut vbd = create_emc2101();
+
+    let dump = sut::dump_registers(&mut vbd);
+    let computed = format!("{dump}");
+
+    assert!(computed.contains("0x00 R   Internal Temperature"));
+    assert!(computed.contains(
+        "0x4B R/W Fan Spin-up                          0x3F FAST=1 STRENGTH=3 DURATION=7\n"
+    ));
+    assert_eq!(computed.lines().count(), 50);
+}
+
+#[test]
+fn restore_registers() {
+    let mut vbd = create_emc2101();
+    let registers_orig = vbd.registers;
+
+    let dump = sut::dump_registers(&mut vbd);
+    // randomize all writeable registers (except 'one shot')
+    for x in sut::hw::defaults::DEFAULTS {
+        let dr = x[0];
+        if dr != 0x0F {
+            vbd.registers[dr as usize].0 = create_random_value::<u8>();
+        }
+    }
+    sut::restore_registers(&mut vbd, &dump);
+
+    let computed = vbd.registers;
+    let expected = registers_orig;
+
+    assert_eq!(computed, expected);
+}
+
 #[test]
 fn reset_device_registers() {
     let mut vbd = create_emc2101();
@@ -736,6 +888,57 @@ fn reset_device_registers() {
     assert_eq!(computed, expected);
 }
 
+#[test]
+fn self_test() {
+    let mut vbd = create_emc2101();
+    let registers_orig = vbd.registers;
+
+    let computed = sut::self_test(&mut vbd);
+    // the virtual device does not mirror the alias registers and the
+    // tach reading does not change
+    let expected = sut::SelfTestReport {
+        manufacturer_id: true,
+        product_id: true,
+        scratch: true,
+        aliases: false,
+        diode: true,
+        tach: false,
+    };
+
+    assert_eq!(computed, expected);
+    assert!(!computed.passed());
+    // all registers must have been restored
+    assert_eq!(vbd.registers, registers_orig);
+}
+
+#[test]
+fn self_test_aliases() {
+    let mut vbd = create_emc2101();
+    for (register, alias) in [
+        (0x03, 0x09),
+        (0x04, 0x0A),
+        (0x05, 0x0B),
+        (0x07, 0x0D),
+        (0x08, 0x0E),
+    ] {
+        vbd.registers[alias] = vbd.registers[register];
+    }
+
+    let computed = sut::self_test(&mut vbd);
+
+    assert!(computed.aliases);
+}
+
+#[test]
+fn self_test_diode_fault() {
+    let mut vbd = create_emc2101();
+    vbd.registers[0x02].0 = 0b0000_0100;
+
+    let computed = sut::self_test(&mut vbd);
+
+    assert!(!computed.diode);
+}
+
 #[test]
 fn validate_device_registers() {
     let mut vbd = create_emc2101();
@@ -746,6 +949,91 @@ fn validate_device_registers() {
     assert_eq!(computed, expected);
 }
 
+#[test]
+fn thermal_monitor_sample() {
+    let mut vbd = create_emc2101();
+    let mut monitor = sut::ThermalMonitor::new();
+
+    vbd.registers[0x00] = (20, false);
+    vbd.registers[0x01] = (30, false);
+    monitor.sample(&mut vbd, 1_000);
+    vbd.registers[0x00] = (30, false);
+    vbd.registers[0x01] = (90, false);
+    monitor.sample(&mut vbd, 1_000);
+
+    let computed = monitor.snapshot();
+    let expected = sut::ThermalSnapshot {
+        internal: sut::Summary {
+            count: 2,
+            min: 20.0,
+            max: 30.0,
+            mean: 25.0,
+            variance: 25.0,
+        },
+        external: sut::Summary {
+            count: 2,
+            min: 30.0,
+            max: 90.0,
+            mean: 60.0,
+            variance: 900.0,
+        },
+        rpm: sut::Summary {
+            count: 2,
+            min: 82.0,
+            max: 82.0,
+            mean: 82.0,
+            variance: 0.0,
+        },
+        time_above_limit_ms: 1_000,
+        diode_faults: 0,
+        rpm_low_events: 0,
+    };
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn thermal_monitor_events() {
+    let mut vbd = create_emc2101();
+    let mut monitor = sut::ThermalMonitor::new();
+
+    // open circuit (0x7F, 0x00) and RPM too low
+    vbd.registers[0x01] = (0x7F, false);
+    vbd.registers[0x02] = (0b0000_0001, false);
+    monitor.sample(&mut vbd, 1_000);
+    monitor.sample(&mut vbd, 1_000);
+    vbd.registers[0x01] = (0x00, false);
+    vbd.registers[0x02] = (0b0000_0000, false);
+    monitor.sample(&mut vbd, 1_000);
+    vbd.registers[0x01] = (0x7F, false);
+    vbd.registers[0x02] = (0b0000_0001, false);
+    monitor.sample(&mut vbd, 1_000);
+
+    let snapshot = monitor.snapshot();
+    let computed = (
+        snapshot.external.count,
+        snapshot.diode_faults,
+        snapshot.rpm_low_events,
+    );
+    let expected = (1, 2, 2);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn thermal_monitor_reset() {
+    let mut vbd = create_emc2101();
+    let mut monitor = sut::ThermalMonitor::new();
+
+    monitor.sample(&mut vbd, 1_000);
+    monitor.reset();
+
+    let computed = monitor.snapshot();
+    let expected = sut::ThermalMonitor::new().snapshot();
+
+    assert_eq!(computed, expected);
+}
+
 // ------------------------------------------------------------------------
 
 fn create_emc2101() -> VirtualI2cBusDevice {
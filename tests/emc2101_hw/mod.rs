@@ -294,11 +294,11 @@ fn set_fan_config() {
     let mut vbd = create_emc2101();
     let val = create_random_value::<u8>();
 
-    // value is automatically clamped to range 0 ≤ x ≤ 31
+    // bit 7 is unused and always written as 0
     sut::set_fan_config(&mut vbd, val);
 
     let computed = sut::get_fan_config(&mut vbd);
-    let expected = val.clamp(0, 31);
+    let expected = val & 0b0111_1111;
 
     assert_eq!(computed, expected);
 }
@@ -639,6 +639,17 @@ fn get_tach_reading() {
     assert_eq!(computed, expected);
 }
 
+#[test]
+fn read_register_block() {
+    let mut vbd = create_emc2101();
+
+    let mut computed = [0x00; 3];
+    sut::read_register_block(&mut vbd, 0xFD, &mut computed);
+    let expected = [0x16, 0x5D, 0x01];
+
+    assert_eq!(computed, expected);
+}
+
 #[test]
 fn reset_device_registers() {
     let mut vbd = create_emc2101();
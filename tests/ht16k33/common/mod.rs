@@ -4,10 +4,13 @@
 
 pub struct VirtualHt16K33 {
     pub dda: [u8; 16], // 0x00
+    pub kda: [u8; 6],  // key data RAM (0x40)
+    pub int: u8,       // INT flag (0x60)
     pub osc: u8,       // system setup register (0x20) (oscillator mode)
     pub dis: u8,       // display setup register (0x80)
     pub ris: u8,       // ROW/INT set register (0xA0)
     pub dim: u8,       // dimming set (0xE0)
+    pub wrc: usize,    // number of write transactions
 }
 
 impl i2c_devices::I2cBusDevice for VirtualHt16K33 {
@@ -19,6 +22,7 @@ impl i2c_devices::I2cBusDevice for VirtualHt16K33 {
 
     fn write_byte(&mut self, da: u8, byte: u8) {
         validate_device_address(da);
+        self.wrc += 1;
 
         let register = byte & 0xF0;
         let value = byte & 0x0F;
@@ -33,31 +37,29 @@ impl i2c_devices::I2cBusDevice for VirtualHt16K33 {
 
     fn write_bytes(&mut self, da: u8, bytes: &[u8]) {
         validate_device_address(da);
-
-        // validate the first byte, copy the remaining 16
-        if bytes[0] == 0x00 {
-            self.dda[0] = bytes[1];
-            self.dda[1] = bytes[2];
-            self.dda[2] = bytes[3];
-            self.dda[3] = bytes[4];
-            self.dda[4] = bytes[5];
-            self.dda[5] = bytes[6];
-            self.dda[6] = bytes[7];
-            self.dda[7] = bytes[8];
-            self.dda[8] = bytes[9];
-            self.dda[9] = bytes[10];
-            self.dda[10] = bytes[11];
-            self.dda[11] = bytes[12];
-            self.dda[12] = bytes[13];
-            self.dda[13] = bytes[14];
-            self.dda[14] = bytes[15];
-            self.dda[15] = bytes[16];
+        self.wrc += 1;
+
+        // the first byte is the start address of the display data RAM,
+        // the address pointer is incremented automatically
+        let start = bytes[0] as usize;
+        let end = start + bytes.len() - 1;
+        if start < 16 && end <= 16 {
+            self.dda[start..end].copy_from_slice(&bytes[1..]);
         } else {
             panic!("invalid write")
         }
     }
 
-    fn read_register_as_byte(&mut self, da: u8, _dr: u8) -> u8 {
+    fn read_register_as_byte(&mut self, da: u8, dr: u8) -> u8 {
+        validate_device_address(da);
+
+        match dr {
+            0x60 => self.int,
+            _ => panic!("function not implemented"),
+        }
+    }
+
+    fn read_register_as_word(&mut self, da: u8, _dr: u8) -> [u8; 2] {
         validate_device_address(da);
 
         panic!("function not implemented")
@@ -91,6 +93,18 @@ impl i2c_devices::I2cBusDevice for VirtualHt16K33 {
         // }
     }
 
+    fn read_register_block(&mut self, da: u8, dr: u8, buffer: &mut [u8]) {
+        validate_device_address(da);
+
+        // the key data RAM must be read in one operation
+        if dr == 0x40 && buffer.len() == 6 {
+            buffer.copy_from_slice(&self.kda);
+            self.int = 0x00;
+        } else {
+            panic!("invalid read")
+        }
+    }
+
     // some hardware functions require a little time to pass
     // - functions that sleep mention this fact in their documentation
     // - sleeping is hardware-dependent, no_std provides no abstraction
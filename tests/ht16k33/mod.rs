@@ -5,10 +5,12 @@
 
 mod common;
 mod convert;
+mod number_format;
 
 use common::VirtualHt16K33;
 
 use i2c_devices::ht16k33 as sut;
+use i2c_devices::ht16k33::LedDisplay; // import trait
 use i2c_devices::ht16k33::SegmentedDisplay; // import trait
 
 // ------------------------------------------------------------------------
@@ -211,12 +213,7 @@ fn convert_14(c: char) -> (u8, u8) {
 fn configure7_and_show_number() {
     let mut vbd = create_ht16k33();
 
-    let sd = sut::Segment7x4 {
-        convert: sut::convert_7,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_number(&mut vbd, 1234.0);
 
     assert_eq!(
@@ -233,17 +230,12 @@ fn configure7_and_show_number() {
 fn configure7_and_show_string() {
     let mut vbd = create_ht16k33();
 
-    let sd = sut::Segment7x4 {
-        convert: sut::convert_7,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_string(&mut vbd, "ABCD");
 
     assert_eq!(
         vbd.dda,
-        [119, 0, 124, 0, 57, 0, 94, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        [119, 0, 124, 0, 0, 0, 57, 0, 94, 0, 0, 0, 0, 0, 0, 0]
     );
     assert_eq!(vbd.dis, 1);
     assert_eq!(vbd.dim, 8);
@@ -255,12 +247,7 @@ fn configure7_and_show_string() {
 fn update7_brightness_pass() {
     let mut vbd = create_ht16k33();
 
-    let mut sd = sut::Segment7x4 {
-        convert: sut::convert_7,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let mut sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
 
     let computed = sd.set_brightness_level(&mut vbd, 8);
     let expected = true;
@@ -272,12 +259,7 @@ fn update7_brightness_pass() {
 fn update7_brightness_fail() {
     let mut vbd = create_ht16k33();
 
-    let mut sd = sut::Segment7x4 {
-        convert: sut::convert_7,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let mut sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
 
     let computed = sd.set_brightness_level(&mut vbd, 16);
     let expected = false;
@@ -289,19 +271,14 @@ fn update7_brightness_fail() {
 fn update7_brightness_and_show() {
     let mut vbd = create_ht16k33();
 
-    let mut sd = sut::Segment7x4 {
-        convert: sut::convert_7,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let mut sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_string(&mut vbd, "ABCD");
 
     sd.set_brightness_level(&mut vbd, 9);
 
     assert_eq!(
         vbd.dda,
-        [119, 0, 124, 0, 57, 0, 94, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        [119, 0, 124, 0, 0, 0, 57, 0, 94, 0, 0, 0, 0, 0, 0, 0]
     );
     assert_eq!(vbd.dis, 1);
     assert_eq!(vbd.dim, 9);
@@ -313,19 +290,14 @@ fn update7_brightness_and_show() {
 fn update7_display_mode_and_show() {
     let mut vbd = create_ht16k33();
 
-    let mut sd = sut::Segment7x4 {
-        convert: sut::convert_7,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let mut sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_string(&mut vbd, "ABCD");
 
-    sd.set_display_mode(&mut vbd, sut::DisplayMode::BlinkSlow);
+    sd.set_display_mode(&mut vbd, sut::BlinkRate::BlinkHalfHz);
 
     assert_eq!(
         vbd.dda,
-        [119, 0, 124, 0, 57, 0, 94, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        [119, 0, 124, 0, 0, 0, 57, 0, 94, 0, 0, 0, 0, 0, 0, 0]
     );
     assert_eq!(vbd.dis, 7);
     assert_eq!(vbd.dim, 8);
@@ -338,12 +310,7 @@ fn disable7() {
     let mut vbd = create_ht16k33();
 
     // configure the display and ensure the device was updated
-    let sd = sut::Segment7x4 {
-        convert: sut::convert_7,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_number(&mut vbd, 1234.0);
 
     // put in standby (disable oscillator)
@@ -360,12 +327,7 @@ fn disable7() {
 fn configure14_and_show_number() {
     let mut vbd = create_ht16k33();
 
-    let sd = sut::Segment14x4 {
-        convert: sut::convert_14,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_number(&mut vbd, 1234.0);
 
     assert_eq!(
@@ -382,12 +344,7 @@ fn configure14_and_show_number() {
 fn configure14_and_show_string() {
     let mut vbd = create_ht16k33();
 
-    let sd = sut::Segment14x4 {
-        convert: sut::convert_14,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_string(&mut vbd, "ABCD");
 
     assert_eq!(
@@ -404,12 +361,7 @@ fn configure14_and_show_string() {
 fn update14_brightness_pass() {
     let mut vbd = create_ht16k33();
 
-    let mut sd = sut::Segment14x4 {
-        convert: sut::convert_14,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let mut sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
 
     let computed = sd.set_brightness_level(&mut vbd, 8);
     let expected = true;
@@ -421,12 +373,7 @@ fn update14_brightness_pass() {
 fn update14_brightness_fail() {
     let mut vbd = create_ht16k33();
 
-    let mut sd = sut::Segment14x4 {
-        convert: sut::convert_14,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let mut sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
 
     let computed = sd.set_brightness_level(&mut vbd, 16);
     let expected = false;
@@ -438,12 +385,7 @@ fn update14_brightness_fail() {
 fn update14_brightness_and_show() {
     let mut vbd = create_ht16k33();
 
-    let mut sd = sut::Segment14x4 {
-        convert: sut::convert_14,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let mut sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_string(&mut vbd, "ABCD");
 
     sd.set_brightness_level(&mut vbd, 9);
@@ -462,15 +404,10 @@ fn update14_brightness_and_show() {
 fn update14_display_mode_and_show() {
     let mut vbd = create_ht16k33();
 
-    let mut sd = sut::Segment14x4 {
-        convert: sut::convert_14,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let mut sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_string(&mut vbd, "ABCD");
 
-    sd.set_display_mode(&mut vbd, sut::DisplayMode::BlinkSlow);
+    sd.set_display_mode(&mut vbd, sut::BlinkRate::BlinkHalfHz);
 
     assert_eq!(
         vbd.dda,
@@ -487,12 +424,7 @@ fn disable14() {
     let mut vbd = create_ht16k33();
 
     // configure the display and ensure the device was updated
-    let sd = sut::Segment14x4 {
-        convert: sut::convert_14,
-        did: 0,
-        display_mode: sut::DisplayMode::On,
-        brightness_level: 8,
-    };
+    let sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
     sd.show_number(&mut vbd, 1234.0);
 
     // put in standby (disable oscillator)
@@ -501,14 +433,1204 @@ fn disable14() {
     assert_eq!(vbd.osc, 0);
 }
 
-// ------------------------------------------------------------------------
+#[test]
+fn show_string_truncated() {
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    let computed = sd.show_string(&mut vbd, "ABCDEFGHIJ");
+    let expected = sut::Fit::Truncated(4);
+
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [247, 0, 143, 18, 57, 0, 15, 18, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn show_string_7x4_colon() {
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    let computed = sd.show_string(&mut vbd, "12:34");
+    let expected = sut::Fit::Complete;
+
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [6, 0, 91, 0, 2, 0, 79, 0, 102, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn show_string_7x4_dots_and_degree() {
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    let computed = sd.show_string(&mut vbd, "21.5°C");
+    let expected = sut::Fit::Complete;
+
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [91, 0, 134, 0, 4, 0, 109, 0, 57, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn show_string_14x4_dots() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment14x4(0);
+    let computed = sd.show_string(&mut vbd, "12.34");
+    let expected = sut::Fit::Complete;
+
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [6, 0, 219, 64, 143, 0, 230, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn show_chars_7x4() {
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::Segment7x4::new(sut::convert_7, 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    let computed = sd.show_chars(&mut vbd, &['A', 'B', 'C', 'D']);
+    let expected = sut::Fit::Complete;
+
+    // the colon (word 2) is skipped
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [119, 0, 124, 0, 0, 0, 57, 0, 94, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn show_chars_7x4_layered_glyphs() {
+    static GLYPHS: sut::LayeredGlyphs<sut::Glyphs7> = sut::LayeredGlyphs {
+        overrides: &[('K', (0b0111_0101, 0b0000_0000))],
+        base: sut::Glyphs7::new(),
+        unknown: Some((0b0100_1001, 0b0000_0000)),
+    };
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::Segment7x4::new(GLYPHS, 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    let computed = sd.show_chars(&mut vbd, &['K', 'Ä', 'h', 'W']);
+    let expected = sut::Fit::Complete;
+
+    // override, base letter, lower case, unknown
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [117, 0, 119, 0, 0, 0, 116, 0, 73, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn show_chars_14x4_closure() {
+    let mut vbd = create_ht16k33();
+
+    // closures can capture state, e.g. to invert all segments
+    let inverted = true;
+    let glyphs = |c| {
+        let (byte1, byte2) = sut::convert_14(c);
+        match inverted {
+            true => (!byte1, !byte2 & 0b0011_1111),
+            false => (byte1, byte2),
+        }
+    };
+    let sd = sut::Segment14x4::new(glyphs, 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    sd.show_chars(&mut vbd, &['1', '1', '1', '1']);
+
+    assert_eq!(
+        vbd.dda[..4],
+        [0b1111_1001, 0b0011_1111, 0b1111_1001, 0b0011_1111]
+    );
+}
+
+#[test]
+fn show_time_7x4() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    let computed = sd.show_time(&mut vbd, 21, 5, sut::TimeFormat::Hour12, true);
+    let expected = true;
+
+    // " 9:05"
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [0, 0, 111, 0, 2, 0, 63, 0, 109, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn show_time_7x4_colon_off() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    sd.show_time(&mut vbd, 12, 34, sut::TimeFormat::Hour24, true);
+    sd.show_time(&mut vbd, 12, 34, sut::TimeFormat::Hour24, false);
+
+    // only the colon (buffer position 4) differs between the frames
+    assert_eq!(
+        vbd.dda,
+        [6, 0, 91, 0, 0, 0, 79, 0, 102, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn show_time_7x4_invalid() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    let computed = sd.show_time(&mut vbd, 24, 0, sut::TimeFormat::Hour24, true);
+    let expected = false;
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dda, [0u8; 16]);
+}
+
+#[test]
+fn show_duration_7x4() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    let computed = sd.show_duration(&mut vbd, 754, true);
+    let expected = true;
+
+    let mut reference = create_ht16k33();
+    create_segment7x4(0).show_string(&mut reference, "12:34");
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dda, reference.dda);
+}
+
+#[test]
+fn show_duration_7x4_hours() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    let computed = sd.show_duration(&mut vbd, 3600, true);
+    let expected = true;
+
+    // no colon, 1 hour must not look like 1 minute (" 1:00")
+    let mut reference = create_ht16k33();
+    create_segment7x4(0).show_string(&mut reference, "1H00");
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dda, reference.dda);
+}
+
+#[test]
+fn show_duration_7x4_overflow() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    let computed = sd.show_duration(&mut vbd, 360_000, true);
+    let expected = false;
+
+    let mut reference = create_ht16k33();
+    create_segment7x4(0).show_string(&mut reference, "--:--");
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dda, reference.dda);
+}
+
+#[test]
+fn countdown_show() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    let mut countdown = sut::Countdown::new(300);
+    countdown.update(1_600);
+    countdown.show(&sd, &mut vbd);
+
+    // 298.4s remaining, colon is off during the second half of a second
+    let mut reference = create_ht16k33();
+    create_segment7x4(0).show_string(&mut reference, " 459");
+
+    assert_eq!(vbd.dda, reference.dda);
+}
+
+#[test]
+fn create_display_fail() {
+    let computed = [
+        sut::Segment7x4::new(sut::convert_7, 8, sut::BlinkRate::NoBlink, 8).err(),
+        sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 16).err(),
+        sut::Matrix8x8::new(9).err(),
+        sut::Keypad::new(255, 2).err(),
+        sut::Keypad::new(0, 0).err(),
+    ];
+    let expected = [
+        Some(sut::ConfigurationError::InvalidDeviceId(8)),
+        Some(sut::ConfigurationError::InvalidBrightnessLevel(16)),
+        Some(sut::ConfigurationError::InvalidDeviceId(9)),
+        Some(sut::ConfigurationError::InvalidDeviceId(255)),
+        Some(sut::ConfigurationError::InvalidDebounceScans(0)),
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn create_display_invalid_wiring() {
+    static WIRING: sut::Wiring<2, 8> = sut::Wiring::by_digit([0, 8], &[]);
+
+    let computed = sut::SegmentDisplay::with_wiring(
+        sut::Glyphs7::new(),
+        &WIRING,
+        0,
+        sut::BlinkRate::NoBlink,
+        8,
+    )
+    .err();
+    let expected = Some(sut::ConfigurationError::InvalidWiring(0, 8));
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn show_string_7x8() {
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::Segment7x8::new(sut::Glyphs7::new(), 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    let computed = sd.show_string(&mut vbd, "1234.5678");
+    let expected = sut::Fit::Complete;
+
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [6, 0, 91, 0, 79, 0, 230, 0, 109, 0, 125, 0, 7, 0, 127, 0]
+    );
+}
+
+#[test]
+fn show_string_16x8() {
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::Segment16x8::new(sut::Glyphs16::new(), 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    let computed = sd.show_string(&mut vbd, "AI");
+    let expected = sut::Fit::Complete;
+
+    // a1, a2, b, c, e, f, g1, g2 | j, m
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda[..4],
+        [0b1100_1111, 0b0000_0011, 0b0000_0000, 0b0100_1000]
+    );
+    assert_eq!(sd.get_digit_count(), 8);
+}
+
+#[test]
+fn show_string_custom_wiring() {
+    // digits from right to left, colon on row 8 of the first digit
+    static WIRING: sut::Wiring<4, 8> = sut::Wiring::by_digit([3, 2, 1, 0], &[(':', (8, 3))]);
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::SegmentDisplay::with_wiring(
+        sut::Glyphs7::new(),
+        &WIRING,
+        0,
+        sut::BlinkRate::NoBlink,
+        8,
+    )
+    .unwrap();
+    let computed = sd.show_string(&mut vbd, "12:34");
+    let expected = sut::Fit::Complete;
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dda[..8], [102, 0, 79, 0, 91, 0, 6, 1]);
+}
+
+#[test]
+fn show_buffer_unchanged() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment14x4(0);
+    sd.show_string(&mut vbd, "1234");
+    let transactions = vbd.wrc;
+    sd.show_string(&mut vbd, "1234");
+
+    // neither setup commands nor display data are sent again
+    let computed = vbd.wrc - transactions;
+    let expected = 0;
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn show_buffer_dirty_range() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment14x4(0);
+    sd.show_string(&mut vbd, "1234");
+    let transactions = vbd.wrc;
+    // not overwritten if only the changed range is sent
+    vbd.dda[0] = 0xFF;
+    vbd.dda[7] = 0xFF;
+    sd.show_string(&mut vbd, "1284");
+
+    let mut reference = create_ht16k33();
+    create_segment14x4(0).show_string(&mut reference, "1284");
+    reference.dda[0] = 0xFF;
+    reference.dda[7] = 0xFF;
+
+    // the third digit uses bytes 4 and 5
+    assert_eq!(vbd.wrc - transactions, 1);
+    assert_eq!(vbd.dda, reference.dda);
+}
+
+#[test]
+fn set_brightness_level_unchanged() {
+    let mut vbd = create_ht16k33();
+
+    let mut sd = create_segment7x4(0);
+    sd.set_brightness_level(&mut vbd, 4);
+    let transactions = vbd.wrc;
+    sd.set_brightness_level(&mut vbd, 4);
+    sd.set_display_mode(&mut vbd, sut::BlinkRate::NoBlink);
+
+    let computed = vbd.wrc - transactions;
+    let expected = 0;
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn disable_and_show() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    sd.show_string(&mut vbd, "1234");
+    sd.disable(&mut vbd);
+    let transactions = vbd.wrc;
+    sd.show_string(&mut vbd, "5678");
+
+    // the display stays in standby
+    assert_eq!(vbd.osc, 0);
+    assert_eq!(vbd.wrc, transactions);
+    assert_eq!(sd.get_power_state(), sut::PowerState::Standby);
+}
+
+#[test]
+fn disable_and_enable() {
+    let mut vbd = create_ht16k33();
+
+    let mut sd = create_segment7x4(0);
+    sd.show_string(&mut vbd, "1234");
+    sd.disable(&mut vbd);
+    sd.set_brightness_level(&mut vbd, 3);
+    sd.set_display_mode(&mut vbd, sut::BlinkRate::BlinkHalfHz);
+    sd.show_string(&mut vbd, "5678");
+    sd.enable(&mut vbd);
+
+    // the configuration and the last frame are restored
+    let mut reference = create_ht16k33();
+    create_segment7x4(0).show_string(&mut reference, "5678");
+
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(vbd.dis, 7);
+    assert_eq!(vbd.dim, 3);
+    assert_eq!(vbd.dda, reference.dda);
+    assert_eq!(sd.get_power_state(), sut::PowerState::Active);
+}
+
+#[test]
+fn power_state_idle() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    sd.show_string(&mut vbd, "1234");
+    sd.set_power_state(&mut vbd, sut::PowerState::Idle);
+
+    // the oscillator keeps running, the display is turned off
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(vbd.dis, 0);
+
+    sd.enable(&mut vbd);
+
+    assert_eq!(vbd.dis, 1);
+}
+
+#[test]
+fn power_state_unchanged() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    sd.show_string(&mut vbd, "1234");
+    sd.disable(&mut vbd);
+    let transactions = vbd.wrc;
+    sd.disable(&mut vbd);
+    sd.enable(&mut vbd);
+    let restored = vbd.wrc;
+    sd.enable(&mut vbd);
+
+    assert_eq!(transactions + 3, restored); // oscillator, blink rate, brightness
+    assert_eq!(vbd.wrc, restored);
+}
+
+#[test]
+fn power_manager_idle_timeout() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    sd.show_string(&mut vbd, "1234");
+    let mut power = sut::PowerManager::new(3);
+    let computed = (0..4)
+        .map(|_| power.tick(&sd, &mut vbd))
+        .collect::<Vec<sut::PowerState>>();
+    let expected = vec![
+        sut::PowerState::Active,
+        sut::PowerState::Active,
+        sut::PowerState::Standby,
+        sut::PowerState::Standby,
+    ];
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.osc, 0);
+
+    power.wake(&sd, &mut vbd);
+
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(power.get_elapsed_ticks(), 0);
+}
+
+#[test]
+fn power_manager_matrix() {
+    let mut vbd = create_ht16k33();
+
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.set_pixel(0, 0);
+    matrix.flush(&mut vbd);
+    let mut power = sut::PowerManager::new(1);
+    let computed = power.tick(&matrix, &mut vbd);
+    let expected = sut::PowerState::Standby;
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.osc, 0);
+
+    power.wake(&matrix, &mut vbd);
+
+    assert_eq!(matrix.get_power_state(), sut::PowerState::Active);
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(&vbd.dda, matrix.get_buffer());
+}
+
+#[test]
+fn power_manager_wake_on_keypress() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment7x4(0);
+    sd.show_string(&mut vbd, "1234");
+    let mut keypad = sut::Keypad::new(0, 1).unwrap();
+    let mut power = sut::PowerManager::new(2);
+    power.scan(&sd, &mut keypad, &mut vbd);
+    power.scan(&sd, &mut keypad, &mut vbd);
+
+    // key scanning requires the oscillator
+    assert_eq!(sd.get_power_state(), sut::PowerState::Idle);
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(vbd.dis, 0);
+
+    vbd.kda = [0x01, 0x00, 0x00, 0x00, 0x00, 0x00];
+    let events: Vec<sut::KeyEvent> = power.scan(&sd, &mut keypad, &mut vbd).collect();
+
+    assert_eq!(events, vec![sut::KeyEvent::Pressed(0)]);
+    assert_eq!(sd.get_power_state(), sut::PowerState::Active);
+    assert_eq!(vbd.dis, 1);
+}
+
+#[test]
+fn adaptive_brightness() {
+    let mut vbd = create_ht16k33();
+
+    let mut sd = create_segment7x4(0);
+    let mut adaptive = sut::AdaptiveBrightness::new(&sut::LUX_CURVE, 0);
+    let computed = [0, 10, 12, 100, 20_000]
+        .into_iter()
+        .map(|lux| adaptive.update(&mut sd, &mut vbd, lux))
+        .collect::<Vec<Option<u8>>>();
+    let expected = vec![Some(0), Some(2), None, Some(6), Some(15)];
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dim, 15);
+    assert_eq!(sd.get_brightness_level(), 15);
+}
+
+#[test]
+fn adaptive_brightness_hysteresis() {
+    let mut vbd = create_ht16k33();
+
+    // level = reading / 10
+    static CURVE: [(u32, u8); 2] = [(0, 0), (150, 15)];
+    let mut sd = create_segment7x4(0);
+    let mut adaptive = sut::AdaptiveBrightness::new(&CURVE, 4);
+    adaptive.update(&mut sd, &mut vbd, 50);
+
+    // flickering around the border of level 5 and 6 is ignored
+    let computed = [54, 56, 53, 57, 62, 57, 52, 49]
+        .into_iter()
+        .map(|reading| adaptive.update(&mut sd, &mut vbd, reading))
+        .collect::<Vec<Option<u8>>>();
+    let expected = vec![None, None, None, None, Some(6), None, None, Some(5)];
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dim, 5);
+}
+
+#[test]
+fn adaptive_brightness_runtime_curve() {
+    let mut vbd = create_ht16k33();
+
+    // e.g. loaded from a configuration file
+    let curve: Vec<(u32, u8)> = (0..=3).map(|idx| (idx * 100, idx as u8 * 5)).collect();
+    let mut sd = create_segment7x4(0);
+    let mut adaptive = sut::AdaptiveBrightness::new(&curve, 0);
+    let computed = [0, 150, 300]
+        .into_iter()
+        .map(|reading| adaptive.update(&mut sd, &mut vbd, reading))
+        .collect::<Vec<Option<u8>>>();
+    let expected = vec![Some(0), Some(8), Some(15)];
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dim, 15);
+}
+
+#[test]
+fn matrix_disable_and_enable() {
+    let mut vbd = create_ht16k33();
+
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.flush(&mut vbd);
+    matrix.disable(&mut vbd);
+    matrix.set_pixel(0, 0);
+    matrix.flush(&mut vbd);
+
+    assert_eq!(vbd.osc, 0);
+    assert_eq!(vbd.dda, [0u8; 16]);
+
+    matrix.enable(&mut vbd);
+
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(&vbd.dda, matrix.get_buffer());
+}
+
+#[test]
+fn force_refresh() {
+    let mut vbd = create_ht16k33();
+
+    let sd = create_segment14x4(0);
+    sd.show_string(&mut vbd, "1234");
+    let reference = vbd.dda;
+
+    // simulate a device reset
+    vbd = create_ht16k33();
+    sd.show_string(&mut vbd, "1234");
+    assert_eq!(vbd.wrc, 0);
+
+    sd.force_refresh(&mut vbd);
+
+    assert_eq!(vbd.dda, reference);
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(vbd.dis, 1);
+    assert_eq!(vbd.dim, 8);
+}
+
+#[test]
+fn animation_fade() {
+    let mut vbd = create_ht16k33();
+
+    let mut sd = create_segment7x4(0);
+    let mut animation = sut::Animation::new(sut::Effect::Fade {
+        from: 15,
+        to: 2,
+        ticks: 3,
+    });
+    let mut count = 1;
+    while animation.tick(&mut sd, &mut vbd) {
+        count += 1;
+    }
+
+    assert_eq!(count, 4);
+    assert_eq!(vbd.dim, 2);
+    assert_eq!(sd.get_brightness_level(), 2);
+    assert!(!animation.tick(&mut sd, &mut vbd));
+}
+
+#[test]
+fn animation_flash() {
+    let mut vbd = create_ht16k33();
+
+    let mut sd = create_segment14x4(0);
+    let mut animation = sut::Animation::new(sut::Effect::Flash {
+        frame: [0xFF; 16],
+        count: 3,
+        ticks_on: 2,
+        ticks_off: 1,
+    });
+    animation.tick(&mut sd, &mut vbd);
+    animation.tick(&mut sd, &mut vbd);
+    animation.tick(&mut sd, &mut vbd);
+    assert_eq!(vbd.dda, [0x00; 16]);
+
+    while animation.tick(&mut sd, &mut vbd) {}
+    assert_eq!(vbd.dda, [0xFF; 16]);
+}
+
+#[test]
+fn marquee_show() {
+    let mut vbd = create_ht16k33();
+
+    let sd = sut::Segment14x4::new(sut::convert_14, 0, sut::BlinkRate::NoBlink, 8).unwrap();
+    let mut marquee = sut::Marquee::new(&sd, "FAN FAILURE", sut::ScrollMode::Left, 2);
+    marquee.tick();
+    marquee.tick();
+    marquee.show(&sd, &mut vbd);
+
+    let mut expected = create_ht16k33();
+    create_segment14x4(0).show_string(&mut expected, "AN F");
+
+    assert_eq!(vbd.dda, expected.dda);
+}
+
+#[test]
+fn marquee_show_dots_and_colon() {
+    let mut vbd = create_ht16k33();
+
+    // the dot is merged into the preceding digit, the colon is an indicator
+    let sd = create_segment7x4(0);
+    let mut marquee = sut::Marquee::new(&sd, "12.34:56", sut::ScrollMode::Left, 1);
+    marquee.step();
+    marquee.show(&sd, &mut vbd);
+
+    let mut expected = create_ht16k33();
+    create_segment7x4(0).show_string(&mut expected, "2.34:5");
+
+    assert_eq!(vbd.dda, expected.dda);
+
+    marquee.step();
+    marquee.show(&sd, &mut vbd);
+    create_segment7x4(0).show_string(&mut expected, "34:56");
+
+    assert_eq!(vbd.dda, expected.dda);
+}
+
+#[test]
+fn display_chain_show_string() {
+    let mut vbd = create_ht16k33();
+
+    let mut chain = sut::DisplayChain::new([create_segment14x4(0), create_segment14x4(1)]).unwrap();
+    let computed = chain.show_string(&mut vbd, "ABCD.EFGH");
+    let expected = sut::Fit::Complete;
+
+    // the virtual device stores the data of the last member only
+    assert_eq!(computed, expected);
+    assert_eq!(
+        vbd.dda,
+        [249, 0, 113, 0, 189, 0, 246, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn display_chain_show_string_truncated() {
+    let mut vbd = create_ht16k33();
+
+    let mut chain = sut::DisplayChain::new([create_segment14x4(0)]).unwrap();
+    let computed = chain.show_string(&mut vbd, "ABCDE");
+    let expected = sut::Fit::Truncated(4);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn display_chain_show_string_long() {
+    let mut vbd = create_ht16k33();
+
+    // 7 members with 8 digits, each digit followed by a dot (112 characters)
+    let mut chain = sut::DisplayChain::new(core::array::from_fn::<_, 7, _>(|did| {
+        create_segment16x8(did as u8)
+    }))
+    .unwrap();
+    let computed = [
+        chain.show_string(&mut vbd, &"8.".repeat(56)),
+        chain.show_string(&mut vbd, &"8.".repeat(57)),
+    ];
+    let expected = [sut::Fit::Complete, sut::Fit::Truncated(112)];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn display_chain_duplicate_did() {
+    let computed = sut::DisplayChain::new([
+        create_segment14x4(0),
+        create_segment14x4(1),
+        create_segment14x4(0),
+    ])
+    .err();
+    let expected = Some(sut::ConfigurationError::DuplicateDeviceId(0));
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn display_chain_show_number() {
+    let mut vbd = create_ht16k33();
+
+    let mut chain = sut::DisplayChain::new([create_segment14x4(0), create_segment14x4(1)]).unwrap();
+    let computed = chain.show_number(&mut vbd, -12.5);
+    let expected = true;
+
+    // "  -12.500" -> "  -1" + "2.500"
+    let mut reference = create_ht16k33();
+    create_segment14x4(1).show_chars(&mut reference, &['2', '.', '5', '0', '0']);
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dda, reference.dda);
+}
+
+#[test]
+fn display_chain_show_number_long() {
+    let mut vbd = create_ht16k33();
+
+    // 5 members with 8 digits (40 digits)
+    let mut chain = sut::DisplayChain::new(core::array::from_fn::<_, 5, _>(|did| {
+        create_segment16x8(did as u8)
+    }))
+    .unwrap();
+    let computed = chain.show_number(&mut vbd, -12.5);
+    let expected = true;
+
+    // right-aligned on the last member
+    let mut reference = create_ht16k33();
+    create_segment16x8(4).show_string(&mut reference, "  -12.500");
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dda, reference.dda);
+}
+
+#[test]
+fn display_chain_show_number_overflow() {
+    let mut vbd = create_ht16k33();
+
+    let mut chain = sut::DisplayChain::new([create_segment14x4(0)]).unwrap();
+    let computed = chain.show_number(&mut vbd, 12345.0);
+    let expected = false;
+
+    let mut reference = create_ht16k33();
+    create_segment14x4(0).show_string(&mut reference, "----");
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dda, reference.dda);
+}
+
+#[test]
+fn display_chain_set_brightness_level() {
+    let mut vbd = create_ht16k33();
+
+    let mut chain = sut::DisplayChain::new([create_segment14x4(0), create_segment14x4(1)]).unwrap();
+    let computed = chain.set_brightness_level(&mut vbd, 3);
+    let expected = true;
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.dim, 3);
+    assert!(chain.members.iter().all(|m| m.get_brightness_level() == 3));
+}
+
+#[test]
+fn display_chain_matrix() {
+    let mut vbd = create_ht16k33();
+
+    let mut chain = sut::DisplayChain::new([
+        sut::Matrix8x8::new(0).unwrap(),
+        sut::Matrix8x8::new(1).unwrap(),
+    ])
+    .unwrap();
+    chain.members[1].set_pixel(0, 0);
+    chain.set_display_mode(&mut vbd, sut::BlinkRate::BlinkHalfHz);
+    chain.flush(&mut vbd);
+
+    assert_eq!(vbd.dis, 7);
+    assert_eq!(vbd.dda[0], 0b0000_0001);
+}
+
+#[test]
+fn display_chain_bar_graph() {
+    let mut vbd = create_ht16k33();
+
+    let mut chain = sut::DisplayChain::new([
+        sut::BarGraph24::new(0).unwrap(),
+        sut::BarGraph24::new(1).unwrap(),
+    ])
+    .unwrap();
+    chain.members[1].set_bar(0, sut::LedColor::Red);
+    let computed = chain.set_brightness_level(&mut vbd, 5);
+    chain.flush(&mut vbd);
+
+    assert!(computed);
+    assert_eq!(vbd.dim, 5);
+    assert_eq!(vbd.dda[0], 0b0000_0001);
+    assert!(chain.members.iter().all(|m| m.get_brightness_level() == 5));
+}
+
+#[test]
+fn keypad_set_interrupt_mode() {
+    let mut vbd = create_ht16k33();
+
+    let keypad = sut::Keypad::new(0, 1).unwrap();
+    keypad.enable(&mut vbd);
+    keypad.set_interrupt_mode(&mut vbd, sut::RowIntMode::IntActiveHigh);
+
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(vbd.ris, 3);
+}
+
+#[test]
+fn keypad_scan() {
+    let mut vbd = create_ht16k33();
+
+    let mut keypad = sut::Keypad::new(0, 1).unwrap();
+    // KS0/K1 and KS1/K4 pressed
+    vbd.kda = [0x01, 0x00, 0x08, 0x00, 0x00, 0x00];
+    let pressed: Vec<sut::KeyEvent> = keypad.scan(&mut vbd).collect();
+    // KS0/K1 released, KS2/K13 pressed
+    vbd.kda = [0x00, 0x00, 0x08, 0x00, 0x00, 0x10];
+    let changed: Vec<sut::KeyEvent> = keypad.scan(&mut vbd).collect();
+
+    assert_eq!(
+        pressed,
+        vec![sut::KeyEvent::Pressed(0), sut::KeyEvent::Pressed(16)]
+    );
+    assert_eq!(
+        changed,
+        vec![sut::KeyEvent::Released(0), sut::KeyEvent::Pressed(38)]
+    );
+    assert!(keypad.is_pressed(16));
+}
+
+#[test]
+fn keypad_debounce() {
+    let mut vbd = create_ht16k33();
+
+    let mut keypad = sut::Keypad::new(0, 3).unwrap();
+    vbd.kda = [0x02, 0x00, 0x00, 0x00, 0x00, 0x00];
+    let first = keypad.scan(&mut vbd).is_empty();
+    let second = keypad.scan(&mut vbd).is_empty();
+    let third: Vec<sut::KeyEvent> = keypad.scan(&mut vbd).collect();
+    let fourth = keypad.scan(&mut vbd).is_empty();
+
+    assert!(first);
+    assert!(second);
+    assert_eq!(third, vec![sut::KeyEvent::Pressed(1)]);
+    assert!(fourth);
+}
+
+#[test]
+fn keypad_debounce_bounce() {
+    let mut keypad = sut::Keypad::new(0, 2).unwrap();
+
+    // a single glitch is ignored
+    let computed = [0b01, 0b00, 0b00]
+        .into_iter()
+        .map(|keys| keypad.update(keys).count())
+        .collect::<Vec<usize>>();
+    let expected = vec![0, 0, 0];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn keypad_get_int_flag() {
+    let mut vbd = create_ht16k33();
+    vbd.int = 0x01;
+
+    let mut keypad = sut::Keypad::new(0, 1).unwrap();
+    let before = keypad.get_int_flag(&mut vbd);
+    let _ = keypad.scan(&mut vbd);
+    let after = keypad.get_int_flag(&mut vbd);
+
+    assert!(before);
+    assert!(!after);
+}
+
+#[test]
+fn matrix8x8_flush() {
+    let mut vbd = create_ht16k33();
+
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.set_pixel(0, 0);
+    matrix.set_pixel(7, 7);
+    matrix.flush(&mut vbd);
+
+    let mut expected = [0u8; 16];
+    expected[0] = 0b0000_0001;
+    expected[14] = 0b1000_0000;
+
+    assert_eq!(vbd.dda, expected);
+    assert_eq!(vbd.osc, 1);
+    assert_eq!(vbd.dim, 15);
+}
+
+#[test]
+fn matrix8x8_flush_dirty_range() {
+    let mut vbd = create_ht16k33();
+
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.flush(&mut vbd);
+    let transactions = vbd.wrc;
+    vbd.dda[0] = 0xFF; // not overwritten if only the changed range is sent
+    matrix.set_pixel(3, 7);
+    matrix.flush(&mut vbd);
+
+    let mut expected = [0u8; 16];
+    expected[0] = 0xFF;
+    expected[14] = 0b0000_1000;
+
+    assert_eq!(vbd.wrc - transactions, 1);
+    assert_eq!(vbd.dda, expected);
+}
+
+#[test]
+fn matrix8x8_clear_pixel() {
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.fill();
+    matrix.clear_pixel(3, 2);
+
+    assert!(!matrix.get_pixel(3, 2));
+    assert!(matrix.get_pixel(2, 3));
+    assert_eq!(matrix.get_buffer()[4], 0b1111_0111);
+    assert_eq!(matrix.get_buffer()[5], 0b0000_0000);
+}
+
+#[test]
+fn matrix8x8_outside() {
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.set_pixel(-1, 0);
+    matrix.set_pixel(8, 0);
+    matrix.set_pixel(0, 8);
+
+    assert_eq!(matrix.get_buffer(), &[0u8; 16]);
+}
+
+#[test]
+fn matrix8x8_rotation() {
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.rotation = sut::Rotation::Rotate90;
+    matrix.set_pixel(0, 0);
+
+    let mut expected = [0u8; 16];
+    expected[0] = 0b1000_0000;
+
+    assert_eq!(matrix.get_buffer(), &expected);
+}
+
+#[test]
+fn matrix8x8_flip() {
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.flip_x = true;
+    matrix.flip_y = true;
+    matrix.set_pixel(0, 0);
+
+    let mut expected = [0u8; 16];
+    expected[14] = 0b1000_0000;
+
+    assert_eq!(matrix.get_buffer(), &expected);
+}
+
+#[test]
+fn matrix8x8_draw_line() {
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.draw_line(0, 1, 7, 1, true);
+    matrix.draw_line(2, 0, 2, 7, true);
+
+    let computed = matrix.get_buffer();
+    let expected = [
+        0x04, 0x00, 0xFF, 0x00, 0x04, 0x00, 0x04, 0x00, 0x04, 0x00, 0x04, 0x00, 0x04, 0x00, 0x04,
+        0x00,
+    ];
+
+    assert_eq!(computed, &expected);
+}
+
+#[test]
+fn matrix8x8_draw_rect() {
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.draw_rect(1, 1, 4, 3, false, true);
+
+    let computed = matrix.get_buffer();
+    let expected = [
+        0x00, 0x00, 0x1E, 0x00, 0x12, 0x00, 0x1E, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+
+    assert_eq!(computed, &expected);
+}
+
+#[test]
+fn matrix8x8_draw_rect_filled() {
+    let mut matrix = sut::Matrix8x8::new(0).unwrap();
+    matrix.draw_rect(1, 1, 4, 3, true, true);
+
+    assert_eq!(matrix.get_buffer()[4], 0x1E);
+}
+
+#[test]
+fn matrix16x8_set_pixel() {
+    let mut matrix = sut::Matrix16x8::new(0).unwrap();
+    matrix.set_pixel(15, 0);
+    matrix.set_pixel(8, 1);
+
+    let mut expected = [0u8; 16];
+    expected[1] = 0b1000_0000;
+    expected[3] = 0b0000_0001;
+
+    assert_eq!(matrix.get_buffer(), &expected);
+    assert_eq!((matrix.get_width(), matrix.get_height()), (16, 8));
+}
+
+#[test]
+fn matrix16x8_rotation() {
+    let mut matrix = sut::Matrix16x8::new(0).unwrap();
+    matrix.rotation = sut::Rotation::Rotate270;
+    // bottom right corner of the rotated (8×16) display
+    matrix.set_pixel(7, 15);
+
+    let mut expected = [0u8; 16];
+    expected[1] = 0b1000_0000;
+
+    assert_eq!(matrix.get_buffer(), &expected);
+    assert_eq!((matrix.get_width(), matrix.get_height()), (8, 16));
+}
+
+#[test]
+fn bicolor_matrix8x8_set_pixel() {
+    let mut matrix = sut::BicolorMatrix8x8::new(0).unwrap();
+    matrix.set_pixel(0, 0, sut::LedColor::Green);
+    matrix.set_pixel(1, 0, sut::LedColor::Red);
+    matrix.set_pixel(2, 0, sut::LedColor::Yellow);
+
+    let mut expected = [0u8; 16];
+    expected[0] = 0b0000_0101;
+    expected[1] = 0b0000_0110;
+
+    assert_eq!(matrix.get_buffer(), &expected);
+    assert_eq!(matrix.get_pixel(2, 0), sut::LedColor::Yellow);
+}
+
+#[test]
+fn bicolor_matrix8x8_clear_pixel() {
+    let mut matrix = sut::BicolorMatrix8x8::new(0).unwrap();
+    matrix.fill(sut::LedColor::Yellow);
+    matrix.clear_pixel(0, 0);
+
+    assert_eq!(matrix.get_pixel(0, 0), sut::LedColor::Off);
+    assert_eq!(matrix.get_pixel(1, 0), sut::LedColor::Yellow);
+}
+
+#[test]
+fn bicolor_matrix8x8_flush() {
+    let mut vbd = create_ht16k33();
+
+    let mut matrix = sut::BicolorMatrix8x8::new(0).unwrap();
+    matrix.draw_line(0, 7, 7, 7, sut::LedColor::Red);
+    matrix.flush(&mut vbd);
+
+    let mut expected = [0u8; 16];
+    expected[15] = 0xFF;
+
+    assert_eq!(vbd.dda, expected);
+}
+
+#[test]
+fn bar_graph24_set_bar() {
+    let mut bar = sut::BarGraph24::new(0).unwrap();
+    bar.set_bar(0, sut::LedColor::Red);
+    bar.set_bar(1, sut::LedColor::Green);
+    bar.set_bar(12, sut::LedColor::Yellow);
+    bar.set_bar(24, sut::LedColor::Yellow); // ignored
+
+    let mut expected = [0u8; 16];
+    expected[0] = 0b0001_0001; // red
+    expected[1] = 0b0001_0010; // green
+
+    assert_eq!(bar.get_buffer(), &expected);
+    assert_eq!(bar.get_bar(12), sut::LedColor::Yellow);
+    assert_eq!(bar.get_bar(24), sut::LedColor::Off);
+}
+
+#[test]
+fn bar_graph24_show_level() {
+    let mut vbd = create_ht16k33();
+
+    let mut bar = sut::BarGraph24::new(0).unwrap();
+    bar.thresholds = sut::BarThresholds { yellow: 2, red: 4 };
+    bar.show_level(&mut vbd, 5, 24);
+
+    let computed = [0, 1, 2, 3, 4, 5].map(|idx| bar.get_bar(idx));
+    let expected = [
+        sut::LedColor::Green,
+        sut::LedColor::Green,
+        sut::LedColor::Yellow,
+        sut::LedColor::Yellow,
+        sut::LedColor::Red,
+        sut::LedColor::Off,
+    ];
+
+    assert_eq!(computed, expected);
+    assert_eq!(&vbd.dda, bar.get_buffer());
+    assert_eq!(vbd.osc, 1);
+}
+
+#[test]
+fn bar_graph24_peak_hold() {
+    let mut vbd = create_ht16k33();
+
+    let mut bar = sut::BarGraph24::new(0).unwrap();
+    bar.peak_hold = Some(2);
+    bar.show_level(&mut vbd, 10, 24);
+    bar.show_level(&mut vbd, 4, 24);
+
+    // the peak is kept for 2 updates
+    assert_eq!(bar.get_peak(), Some(10));
+    assert_eq!(bar.get_bar(9), sut::LedColor::Green);
+    assert_eq!(bar.get_bar(8), sut::LedColor::Off);
+
+    bar.show_level(&mut vbd, 4, 24);
+    assert_eq!(bar.get_peak(), Some(10));
+    bar.show_level(&mut vbd, 4, 24);
+    assert_eq!(bar.get_peak(), Some(4));
+    assert_eq!(bar.get_bar(9), sut::LedColor::Off);
+    assert_eq!(&vbd.dda, bar.get_buffer());
+}
+
+// ------------------------------------------------------------------------
+
+fn create_segment7x4(did: u8) -> sut::Segment7x4 {
+    sut::Segment7x4::new(sut::Glyphs7::new(), did, sut::BlinkRate::NoBlink, 8).unwrap()
+}
+
+fn create_segment14x4(did: u8) -> sut::Segment14x4 {
+    sut::Segment14x4::new(sut::Glyphs14::new(), did, sut::BlinkRate::NoBlink, 8).unwrap()
+}
+
+fn create_segment16x8(did: u8) -> sut::Segment16x8 {
+    sut::Segment16x8::new(sut::Glyphs16::new(), did, sut::BlinkRate::NoBlink, 8).unwrap()
+}
 
 fn create_ht16k33() -> VirtualHt16K33 {
     VirtualHt16K33 {
         dda: [0u8; 16],
+        kda: [0u8; 6],
+        int: 0x00,
         osc: 0x00,
         dis: 0x00,
         ris: 0x00,
         dim: 0x00,
+        wrc: 0,
     }
 }
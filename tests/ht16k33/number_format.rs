@@ -0,0 +1,396 @@
+use i2c_devices::ht16k33 as sut;
+use i2c_devices::ht16k33::{Alignment, Decimals, NumberFormat, Overflow, Radix};
+
+fn format_float(format: &NumberFormat, number: f32) -> String {
+    format.format_float(number).to_string()
+}
+
+fn format_integer(format: &NumberFormat, number: i64) -> String {
+    format.format_integer(number).to_string()
+}
+
+/// round the exact decimal expansion of the number (ties away from zero)
+/// - independent of the implementation, uses string arithmetic only
+fn round_reference(number: f32, decimals: usize) -> String {
+    // f32 values have at most 149 fractional digits, the expansion is exact
+    let exact = format!("{:.150}", number.abs());
+    let (integer, fraction) = exact.split_once('.').unwrap();
+    let mut digits: Vec<u8> = integer
+        .bytes()
+        .chain(fraction.bytes().take(decimals))
+        .map(|b| b - b'0')
+        .collect();
+
+    if fraction.as_bytes()[decimals] >= b'5' {
+        let mut idx = digits.len();
+        loop {
+            if idx == 0 {
+                digits.insert(0, 1);
+                break;
+            }
+            idx -= 1;
+            if digits[idx] == 9 {
+                digits[idx] = 0;
+            } else {
+                digits[idx] += 1;
+                break;
+            }
+        }
+    }
+
+    let is_zero = digits.iter().all(|d| *d == 0);
+    let split = digits.len() - decimals;
+    let mut result = String::new();
+    if number.is_sign_negative() && !is_zero {
+        result.push('-');
+    }
+    for d in &digits[..split] {
+        result.push((b'0' + d) as char);
+    }
+    if decimals > 0 {
+        result.push('.');
+        for d in &digits[split..] {
+            result.push((b'0' + d) as char);
+        }
+    }
+
+    result
+}
+
+// ------------------------------------------------------------------------
+// decimal numbers
+// ------------------------------------------------------------------------
+
+#[test]
+fn format_float_auto() {
+    let computed = [
+        format_float(&NumberFormat::new(8), 1.23456),
+        format_float(&NumberFormat::new(8), -12.345),
+        format_float(&NumberFormat::new(4), 1234.5),
+        format_float(&NumberFormat::new(4), 0.05),
+        format_float(&NumberFormat::new(4), 9.9996),
+        format_float(&NumberFormat::new(4), -0.0001),
+        format_float(&NumberFormat::new(2), 42.0),
+    ];
+    let expected = [
+        "    1.235",
+        "  -12.345",
+        "1235",
+        "0.050",
+        "10.00",
+        "0.000",
+        "42",
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_overflow() {
+    let computed = [
+        format_float(&NumberFormat::new(4), 12345.0),
+        format_float(&NumberFormat::new(4), -1000.0),
+        format_float(&NumberFormat::new(4), f32::NAN),
+        format_float(&NumberFormat::new(4), f32::NEG_INFINITY),
+        format_float(&NumberFormat::new(4), 9999.5),
+    ];
+    let expected = ["----", "----", "----", "----", "----"];
+
+    assert_eq!(computed, expected);
+    assert!(NumberFormat::new(4).format_float(12345.0).is_overflow());
+}
+
+#[test]
+fn format_float_overflow_non_ascii() {
+    let format = NumberFormat {
+        overflow: Overflow::Fill('°'),
+        ..NumberFormat::new(4)
+    };
+    let computed = format_float(&format, 12345.0);
+    let expected = "°°°°";
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_negative_fraction() {
+    // the sign must not get lost if the integer part is 0
+    let computed = [
+        format_float(&NumberFormat::new(4), -0.5),
+        format_float(&NumberFormat::new(4), -0.05),
+        format_float(&NumberFormat::new(2), -0.5),
+        format_float(&NumberFormat::new(1), -0.4),
+    ];
+    let expected = ["-0.50", "-0.05", "-1", "0"];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_fixed() {
+    let format = NumberFormat {
+        decimals: Decimals::Fixed(1),
+        ..NumberFormat::new(4)
+    };
+
+    let computed = [
+        format_float(&format, 21.46),
+        format_float(&format, -1.0),
+        format_float(&format, 0.25),
+        format_float(&format, 999.94),
+        format_float(&format, 999.95),
+    ];
+    let expected = [" 21.5", " -1.0", "  0.3", "999.9", "----"];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_leading_zeros() {
+    let format = NumberFormat {
+        decimals: Decimals::Fixed(1),
+        leading_zeros: true,
+        ..NumberFormat::new(5)
+    };
+
+    let computed = [
+        format_float(&format, 1.5),
+        format_float(&format, -1.5),
+        format_float(&format, 1234.5),
+    ];
+    let expected = ["0001.5", "-001.5", "1234.5"];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_left_aligned() {
+    let format = NumberFormat {
+        alignment: Alignment::Left,
+        ..NumberFormat::new(6)
+    };
+
+    let computed = [format_float(&format, 1.5), format_float(&format, -12.0)];
+    let expected = ["1.500  ", "-12.000"];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_hexadecimal() {
+    let format = NumberFormat {
+        radix: Radix::Hexadecimal,
+        ..NumberFormat::new(4)
+    };
+
+    let computed = [
+        format_float(&format, 254.6),
+        format_float(&format, -10.0),
+        format_float(&format, 65536.0),
+    ];
+    let expected = ["  FF", "  -A", "----"];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_overflow_text() {
+    let format = NumberFormat {
+        alignment: Alignment::Left,
+        overflow: Overflow::Text("HIGH", "LOW"),
+        ..NumberFormat::new(5)
+    };
+
+    let computed = [
+        format_float(&format, 100000.0),
+        format_float(&format, -10000.0),
+        format_float(
+            &NumberFormat {
+                digits: 2,
+                ..format
+            },
+            100.0,
+        ),
+    ];
+    let expected = ["HIGH ", "LOW  ", "HI"];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_rounding_ties() {
+    // exactly representable ties are rounded away from zero
+    let format = NumberFormat {
+        decimals: Decimals::Fixed(0),
+        ..NumberFormat::new(8)
+    };
+    for value in -1000..1000 {
+        let number = value as f32 + 0.5;
+        let computed = format_float(&format, number);
+        let expected = format!("{:>8}", round_reference(number, 0));
+
+        assert_eq!(computed, expected, "{number}");
+    }
+
+    let format = NumberFormat {
+        decimals: Decimals::Fixed(2),
+        ..NumberFormat::new(4)
+    };
+    let computed = [
+        format_float(&format, 0.125),
+        format_float(&format, -0.125),
+        format_float(&format, 1.005), // 1.00499999523...
+        format_float(&format, 2.675), // 2.67499995231...
+    ];
+    let expected = [" 0.13", "-0.13", " 1.00", " 2.67"];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_float_rounding_exhaustive() {
+    // compare a wide range of f32 values with the reference implementation
+    let limit = 1.0e7f32.to_bits();
+    for decimals in 0..=4 {
+        let format = NumberFormat {
+            digits: 16,
+            decimals: Decimals::Fixed(decimals),
+            alignment: Alignment::Left,
+            ..NumberFormat::new(0)
+        };
+        for bits in (0..limit).step_by(12_289) {
+            for number in [f32::from_bits(bits), -f32::from_bits(bits)] {
+                let computed = format_float(&format, number);
+                let expected = round_reference(number, decimals as usize);
+
+                assert_eq!(computed.trim_end(), expected, "{number:e}");
+            }
+        }
+    }
+}
+
+#[test]
+fn format_float_rounding_random() {
+    use rand::Rng;
+
+    let mut rng = rand::rng();
+    let format = NumberFormat {
+        digits: 16,
+        decimals: Decimals::Fixed(3),
+        alignment: Alignment::Left,
+        ..NumberFormat::new(0)
+    };
+    for _ in 0..100_000 {
+        let number: f32 = rng.random_range(-100_000.0..100_000.0);
+        let computed = format_float(&format, number);
+        let expected = round_reference(number, 3);
+
+        assert_eq!(computed.trim_end(), expected, "{number:e}");
+    }
+}
+
+// ------------------------------------------------------------------------
+// integer numbers
+// ------------------------------------------------------------------------
+
+#[test]
+fn format_integer_exhaustive() {
+    let decimal = NumberFormat {
+        alignment: Alignment::Left,
+        ..NumberFormat::new(8)
+    };
+    let hexadecimal = NumberFormat {
+        radix: Radix::Hexadecimal,
+        ..decimal
+    };
+    for number in -999_999i64..=999_999 {
+        assert_eq!(format_integer(&decimal, number), format!("{number:<8}"));
+        let sign = if number < 0 { "-" } else { "" };
+        let expected = format!("{sign}{:X}", number.unsigned_abs());
+        assert_eq!(
+            format_integer(&hexadecimal, number),
+            format!("{expected:<8}")
+        );
+    }
+}
+
+#[test]
+fn format_integer_pass() {
+    let fixed = NumberFormat {
+        decimals: Decimals::Fixed(2),
+        ..NumberFormat::new(6)
+    };
+    let zeros = NumberFormat {
+        leading_zeros: true,
+        ..NumberFormat::new(4)
+    };
+
+    let computed = [
+        format_integer(&fixed, -7),
+        format_integer(&zeros, 42),
+        format_integer(&zeros, -42),
+        format_integer(&NumberFormat::new(4), 0),
+        format_integer(&NumberFormat::new(20), i64::MIN),
+    ];
+    let expected = ["  -7.00", "0042", "-042", "   0", "-9223372036854775808"];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn format_integer_overflow() {
+    let computed = [
+        format_integer(&NumberFormat::new(4), 10000),
+        format_integer(&NumberFormat::new(4), -1000),
+        format_integer(
+            &NumberFormat {
+                overflow: Overflow::Fill('E'),
+                ..NumberFormat::new(2)
+            },
+            100,
+        ),
+    ];
+    let expected = ["----", "----", "EE"];
+
+    assert_eq!(computed, expected);
+}
+
+// ------------------------------------------------------------------------
+// displays
+// ------------------------------------------------------------------------
+
+// build the expected buffer, the dot is merged into the preceding digit
+fn make_buffer(chars: &[char]) -> [u8; 16] {
+    let mut buffer = [0u8; 16];
+    let mut idx = 0;
+    for c in chars {
+        let (byte1, byte2) = sut::convert_14(*c);
+        if *c == '.' {
+            buffer[2 * idx - 2] |= byte1;
+            buffer[2 * idx - 1] |= byte2;
+        } else {
+            buffer[2 * idx] = byte1;
+            buffer[2 * idx + 1] = byte2;
+            idx += 1;
+        }
+    }
+
+    buffer
+}
+
+#[test]
+fn convert_to_4_digits_negative_fraction() {
+    let computed = sut::convert_to_4_digits(-0.5, sut::convert_14);
+    let expected = make_buffer(&['-', '0', '.', '5', '0']);
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn convert_to_4_digits_leading_zero_fraction() {
+    // the zero after the dot must not get lost
+    let computed = sut::convert_to_4_digits(1.05, sut::convert_14);
+    let expected = make_buffer(&['1', '.', '0', '5', '0']);
+
+    assert_eq!(computed, expected);
+}
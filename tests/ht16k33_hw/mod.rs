@@ -8,6 +8,7 @@ mod common;
 use common::VirtualHt16K33;
 
 use i2c_devices::ht16k33::hw as sut;
+use i2c_devices::ht16k33::ConfigurationError;
 
 // ------------------------------------------------------------------------
 
@@ -21,24 +22,83 @@ fn set_display_data_pass() {
 }
 
 #[test]
-fn set_blink_rate_pass() {
+fn set_display_data_range_pass() {
     let mut vbd = create_ht16k33();
 
-    let computed = sut::set_blink_rate(&mut vbd, 0x70, 3);
-    let expected = true;
+    let start = sut::DisplayRamAddress::new::<14>();
+    let computed = sut::set_display_data_range(&mut vbd, 0x70, start, &[0xAA, 0x55]);
+    let expected = Ok(());
+
+    let mut data = [0u8; 16];
+    data[14] = 0xAA;
+    data[15] = 0x55;
 
     assert_eq!(computed, expected);
-    assert_eq!(vbd.dis, 3);
+    assert_eq!(vbd.dda, data);
 }
 
 #[test]
-fn set_blink_rate_fail() {
+fn set_display_data_range_fail() {
     let mut vbd = create_ht16k33();
 
-    let computed = sut::set_blink_rate(&mut vbd, 0x70, 99);
-    let expected = false;
+    let start = sut::DisplayRamAddress::new::<15>();
+    let computed = sut::set_display_data_range(&mut vbd, 0x70, start, &[0xAA, 0x55]);
+    let expected = Err(ConfigurationError::InvalidDataLength(2));
 
     assert_eq!(computed, expected);
+    assert_eq!(vbd.wrc, 0);
+}
+
+#[test]
+fn send_command() {
+    let mut vbd = create_ht16k33();
+
+    sut::send_command(
+        &mut vbd,
+        0x70,
+        sut::Command::DimmingSet(sut::Dimming::Duty8),
+    );
+
+    assert_eq!(vbd.dim, 7);
+    assert_eq!(vbd.wrc, 1);
+}
+
+#[test]
+fn decode_command() {
+    let computed = [
+        sut::Command::decode(0x21),
+        sut::Command::decode(0x85),
+        sut::Command::decode(0xA1),
+        sut::Command::decode(0xEF),
+        sut::Command::decode(0xFF),
+    ];
+    let expected = [
+        Some(sut::Command::SystemSetup(sut::Oscillator::On)),
+        Some(sut::Command::DisplaySetup(sut::BlinkRate::Blink1Hz)),
+        Some(sut::Command::RowIntSet(sut::RowIntMode::IntActiveLow)),
+        Some(sut::Command::DimmingSet(sut::Dimming::Duty16)),
+        None,
+    ];
+
+    assert_eq!(computed, expected);
+}
+
+#[test]
+fn set_blink_rate_pass() {
+    let mut vbd = create_ht16k33();
+
+    sut::set_blink_rate(&mut vbd, 0x70, sut::BlinkRate::Blink2Hz);
+
+    assert_eq!(vbd.dis, 3);
+}
+
+#[test]
+fn set_blink_rate_display_off() {
+    let mut vbd = create_ht16k33();
+    vbd.dis = 0x03;
+
+    sut::set_blink_rate(&mut vbd, 0x70, sut::BlinkRate::DisplayOff);
+
     assert_eq!(vbd.dis, 0);
 }
 
@@ -46,43 +106,38 @@ fn set_blink_rate_fail() {
 fn set_brightness_level_pass() {
     let mut vbd = create_ht16k33();
 
-    let computed = sut::set_brightness_level(&mut vbd, 0x70, 2);
-    let expected = true;
+    sut::set_brightness_level(&mut vbd, 0x70, sut::Dimming::Duty3);
 
-    assert_eq!(computed, expected);
     assert_eq!(vbd.dim, 2);
 }
 
 #[test]
-fn set_brightness_level_fail() {
+fn set_brightness_level_from_level() {
     let mut vbd = create_ht16k33();
 
-    let computed = sut::set_brightness_level(&mut vbd, 0x70, 99);
-    let expected = false;
+    let dimming = sut::Dimming::try_from(9).unwrap();
+    sut::set_brightness_level(&mut vbd, 0x70, dimming);
 
-    assert_eq!(computed, expected);
-    assert_eq!(vbd.dim, 0);
+    assert_eq!(vbd.dim, 9);
+    assert!(sut::Dimming::try_from(99).is_err());
 }
 
 #[test]
 fn set_output_select_pass() {
     let mut vbd = create_ht16k33();
 
-    let computed = sut::set_output_select(&mut vbd, 0x70, 1);
-    let expected = true;
+    sut::set_output_select(&mut vbd, 0x70, sut::RowIntMode::IntActiveLow);
 
-    assert_eq!(computed, expected);
     assert_eq!(vbd.ris, 1);
 }
 
 #[test]
-fn set_output_select_fail() {
+fn set_output_select_row() {
     let mut vbd = create_ht16k33();
+    vbd.ris = 0x03;
 
-    let computed = sut::set_output_select(&mut vbd, 0x70, 99);
-    let expected = false;
+    sut::set_output_select(&mut vbd, 0x70, sut::RowIntMode::Row);
 
-    assert_eq!(computed, expected);
     assert_eq!(vbd.ris, 0);
 }
 
@@ -90,32 +145,56 @@ fn set_output_select_fail() {
 fn set_oscillator_mode_pass() {
     let mut vbd = create_ht16k33();
 
-    let computed = sut::set_oscillator_mode(&mut vbd, 0x70, 1);
-    let expected = true;
+    sut::set_oscillator_mode(&mut vbd, 0x70, sut::Oscillator::On);
 
-    assert_eq!(computed, expected);
     assert_eq!(vbd.osc, 1);
 }
 
 #[test]
-fn set_oscillator_mode_fail() {
+fn set_oscillator_mode_off() {
     let mut vbd = create_ht16k33();
+    vbd.osc = 0x01;
 
-    let computed = sut::set_oscillator_mode(&mut vbd, 0x70, 99);
-    let expected = false;
+    sut::set_oscillator_mode(&mut vbd, 0x70, sut::Oscillator::Off);
 
-    assert_eq!(computed, expected);
     assert_eq!(vbd.osc, 0);
 }
 
+#[test]
+fn get_key_data() {
+    let mut vbd = create_ht16k33();
+    vbd.kda = [0x01, 0x00, 0x08, 0x00, 0x00, 0x10];
+    vbd.int = 0x01;
+
+    let computed = sut::get_key_data(&mut vbd, 0x70);
+    let expected = [0x01, 0x00, 0x08, 0x00, 0x00, 0x10];
+
+    assert_eq!(computed, expected);
+    assert_eq!(vbd.int, 0x00);
+}
+
+#[test]
+fn get_int_flag() {
+    let mut vbd = create_ht16k33();
+    vbd.int = 0xFF;
+
+    let computed = sut::get_int_flag(&mut vbd, 0x70);
+    let expected = true;
+
+    assert_eq!(computed, expected);
+}
+
 // ------------------------------------------------------------------------
 
 fn create_ht16k33() -> VirtualHt16K33 {
     VirtualHt16K33 {
         dda: [0u8; 16],
+        kda: [0u8; 6],
+        int: 0x00,
         osc: 0x00,
         dis: 0x00,
         ris: 0x00,
         dim: 0x00,
+        wrc: 0,
     }
 }
@@ -15,6 +15,8 @@ cargo llvm-cov nextest --lcov --output-path ./target/lcov.info
 ```
 */
 
+mod ads1x1x;
+mod ads1x1x_hw;
 mod emc2101;
 mod emc2101_hw;
 mod ht16k33;